@@ -0,0 +1,288 @@
+use chrono::{DateTime, Datelike, FixedOffset};
+use icu_calendar::cal::{ChineseTraditional, Hijri, Persian};
+use icu_calendar::Date;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Language;
+
+/// Secondary calendar rendered next to the Gregorian date
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarSystem {
+    #[default]
+    None,
+    /// Chinese lunisolar calendar with zodiac year and solar term
+    Chinese,
+    /// Persian (Solar Hijri) calendar
+    Persian,
+    /// Islamic (Hijri) calendar, Umm al-Qura variant
+    Islamic,
+}
+
+const HEAVENLY_STEMS: [&str; 10] = ["甲", "乙", "丙", "丁", "戊", "己", "庚", "辛", "壬", "癸"];
+const EARTHLY_BRANCHES: [&str; 12] = [
+    "子", "丑", "寅", "卯", "辰", "巳", "午", "未", "申", "酉", "戌", "亥",
+];
+const ZODIAC_ZH: [&str; 12] = [
+    "鼠", "牛", "虎", "兔", "龙", "蛇", "马", "羊", "猴", "鸡", "狗", "猪",
+];
+const ZODIAC_EN: [&str; 12] = [
+    "Rat", "Ox", "Tiger", "Rabbit", "Dragon", "Snake", "Horse", "Goat", "Monkey", "Rooster", "Dog",
+    "Pig",
+];
+const LUNAR_MONTHS_ZH: [&str; 12] = [
+    "正", "二", "三", "四", "五", "六", "七", "八", "九", "十", "冬", "腊",
+];
+
+/// Solar terms in order of the sun's apparent longitude, starting at 0° (vernal equinox)
+const SOLAR_TERMS_ZH: [&str; 24] = [
+    "春分", "清明", "谷雨", "立夏", "小满", "芒种", "夏至", "小暑", "大暑", "立秋", "处暑", "白露",
+    "秋分", "寒露", "霜降", "立冬", "小雪", "大雪", "冬至", "小寒", "大寒", "立春", "雨水", "惊蛰",
+];
+const SOLAR_TERMS_EN: [&str; 24] = [
+    "Spring Equinox",
+    "Clear and Bright",
+    "Grain Rain",
+    "Start of Summer",
+    "Grain Buds",
+    "Grain in Ear",
+    "Summer Solstice",
+    "Minor Heat",
+    "Major Heat",
+    "Start of Autumn",
+    "End of Heat",
+    "White Dew",
+    "Autumn Equinox",
+    "Cold Dew",
+    "Frost's Descent",
+    "Start of Winter",
+    "Minor Snow",
+    "Major Snow",
+    "Winter Solstice",
+    "Minor Cold",
+    "Major Cold",
+    "Start of Spring",
+    "Rain Water",
+    "Awakening of Insects",
+];
+
+const PERSIAN_MONTHS: [&str; 12] = [
+    "Farvardin",
+    "Ordibehesht",
+    "Khordad",
+    "Tir",
+    "Mordad",
+    "Shahrivar",
+    "Mehr",
+    "Aban",
+    "Azar",
+    "Dey",
+    "Bahman",
+    "Esfand",
+];
+const HIJRI_MONTHS: [&str; 12] = [
+    "Muharram",
+    "Safar",
+    "Rabi al-Awwal",
+    "Rabi al-Thani",
+    "Jumada al-Awwal",
+    "Jumada al-Thani",
+    "Rajab",
+    "Shaban",
+    "Ramadan",
+    "Shawwal",
+    "Dhu al-Qadah",
+    "Dhu al-Hijjah",
+];
+
+/// Render the local date of `datetime` in the selected calendar system
+pub fn render(
+    system: CalendarSystem,
+    datetime: &DateTime<FixedOffset>,
+    language: Language,
+) -> Option<String> {
+    if system == CalendarSystem::None {
+        return None;
+    }
+
+    let iso = Date::try_new_iso(
+        datetime.year(),
+        datetime.month() as u8,
+        datetime.day() as u8,
+    )
+    .ok()?;
+
+    Some(match system {
+        CalendarSystem::None => return None,
+        CalendarSystem::Chinese => render_chinese(
+            iso.to_calendar(ChineseTraditional::new()),
+            datetime,
+            language,
+        ),
+        CalendarSystem::Persian => {
+            let date = iso.to_calendar(Persian::new());
+            let (year, month, day) = (
+                date.era_year().year,
+                date.month().number(),
+                date.day_of_month().0,
+            );
+            match language {
+                Language::English => {
+                    format!("{} {} {} AP", day, PERSIAN_MONTHS[month as usize - 1], year)
+                }
+                Language::SimplifiedChinese => format!("伊朗历 {}年{}月{}日", year, month, day),
+            }
+        }
+        CalendarSystem::Islamic => {
+            let date = iso.to_calendar(Hijri::new_umm_al_qura());
+            let (year, month, day) = (
+                date.era_year().year,
+                date.month().number(),
+                date.day_of_month().0,
+            );
+            match language {
+                Language::English => {
+                    format!("{} {} {} AH", day, HIJRI_MONTHS[month as usize - 1], year)
+                }
+                Language::SimplifiedChinese => format!("伊斯兰历 {}年{}月{}日", year, month, day),
+            }
+        }
+    })
+}
+
+fn render_chinese(
+    date: Date<ChineseTraditional>,
+    datetime: &DateTime<FixedOffset>,
+    language: Language,
+) -> String {
+    // Cyclic years are 1-based within the 60-year sexagenary cycle
+    let cycle_index = (date.cyclic_year().year as usize + 59) % 60;
+    let stem = HEAVENLY_STEMS[cycle_index % 10];
+    let branch_index = cycle_index % 12;
+    let month = date.month().to_input();
+    let day = date.day_of_month().0;
+    let term = solar_term_index(datetime.timestamp());
+
+    match language {
+        Language::SimplifiedChinese => format!(
+            "{}{}{}年 {}{}月{} · {}",
+            stem,
+            EARTHLY_BRANCHES[branch_index],
+            ZODIAC_ZH[branch_index],
+            if month.is_leap() { "闰" } else { "" },
+            LUNAR_MONTHS_ZH[month.number() as usize - 1],
+            chinese_day(day),
+            SOLAR_TERMS_ZH[term]
+        ),
+        Language::English => format!(
+            "Lunar {}month {}, day {} · Year of the {} ({}{}) · {}",
+            if month.is_leap() { "leap " } else { "" },
+            month.number(),
+            day,
+            ZODIAC_EN[branch_index],
+            stem,
+            EARTHLY_BRANCHES[branch_index],
+            SOLAR_TERMS_EN[term]
+        ),
+    }
+}
+
+/// Traditional day names: 初一 … 初十, 十一 … 二十, 廿一 … 三十
+fn chinese_day(day: u8) -> String {
+    const DIGITS: [&str; 10] = ["", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+    match day {
+        10 => "初十".to_string(),
+        20 => "二十".to_string(),
+        30 => "三十".to_string(),
+        1..=9 => format!("初{}", DIGITS[day as usize]),
+        11..=19 => format!("十{}", DIGITS[day as usize - 10]),
+        21..=29 => format!("廿{}", DIGITS[day as usize - 20]),
+        _ => day.to_string(),
+    }
+}
+
+/// Index into the solar term tables for the term in effect at the given instant.
+///
+/// Uses the low-precision solar longitude from Meeus, *Astronomical Algorithms*
+/// ch. 25, accurate to about 0.01°, i.e. within roughly 15 minutes of a term boundary.
+fn solar_term_index(timestamp_seconds: i64) -> usize {
+    let julian_day = timestamp_seconds as f64 / 86400.0 + 2440587.5;
+    let t = (julian_day - 2451545.0) / 36525.0;
+
+    let mean_longitude = 280.46646 + 36000.76983 * t + 0.0003032 * t * t;
+    let mean_anomaly = (357.52911 + 35999.05029 * t - 0.0001537 * t * t).to_radians();
+    let center = (1.914602 - 0.004817 * t - 0.000014 * t * t) * mean_anomaly.sin()
+        + (0.019993 - 0.000101 * t) * (2.0 * mean_anomaly).sin()
+        + 0.000289 * (3.0 * mean_anomaly).sin();
+    let omega = (125.04 - 1934.136 * t).to_radians();
+    let apparent = mean_longitude + center - 0.00569 - 0.00478 * omega.sin();
+
+    (apparent.rem_euclid(360.0) / 15.0) as usize % 24
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn shanghai(y: i32, m: u32, d: u32, h: u32) -> DateTime<FixedOffset> {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        offset.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_chinese_new_year() {
+        let datetime = shanghai(2024, 2, 10, 12);
+        let rendered = render(
+            CalendarSystem::Chinese,
+            &datetime,
+            Language::SimplifiedChinese,
+        );
+        // Start of Spring fell on 2024-02-04
+        assert_eq!(rendered.as_deref(), Some("甲辰龙年 正月初一 · 立春"));
+
+        let rendered = render(CalendarSystem::Chinese, &datetime, Language::English).unwrap();
+        assert!(rendered.contains("Year of the Dragon"));
+    }
+
+    #[test]
+    fn test_chinese_leap_month() {
+        // 2023 had a leap second month
+        let datetime = shanghai(2023, 4, 1, 12);
+        let rendered = render(
+            CalendarSystem::Chinese,
+            &datetime,
+            Language::SimplifiedChinese,
+        );
+        assert_eq!(rendered.as_deref(), Some("癸卯兔年 闰二月十一 · 春分"));
+    }
+
+    #[test]
+    fn test_solar_terms() {
+        // Winter solstice 2023 was at 2023-12-22 03:27 UTC
+        let before = Utc.with_ymd_and_hms(2023, 12, 22, 2, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2023, 12, 22, 5, 0, 0).unwrap();
+        assert_eq!(SOLAR_TERMS_ZH[solar_term_index(before.timestamp())], "大雪");
+        assert_eq!(SOLAR_TERMS_ZH[solar_term_index(after.timestamp())], "冬至");
+    }
+
+    #[test]
+    fn test_persian_and_islamic() {
+        let datetime = Utc
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            .fixed_offset();
+        assert_eq!(
+            render(CalendarSystem::Persian, &datetime, Language::English).as_deref(),
+            Some("11 Dey 1402 AP")
+        );
+        assert_eq!(
+            render(CalendarSystem::Islamic, &datetime, Language::English).as_deref(),
+            Some("19 Jumada al-Thani 1445 AH")
+        );
+        assert_eq!(
+            render(CalendarSystem::None, &datetime, Language::English),
+            None
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use chrono::{DateTime, Duration, FixedOffset, Months, Utc};
+
+use crate::duration::{split_components, NANOS_PER_MILLI, NANOS_PER_SECOND};
+use crate::literal::{self, DateOrder};
+use crate::zone::Zone;
+
+/// Longest expression worth trying, e.g. `2024-01-01T00:00:00+08:00 - 2023-06-30T12:00:00Z`
+const MAX_INPUT_CHARS: usize = 80;
+
+/// Bare integers with fewer digits than this on the right of an operator are
+/// second counts rather than epochs (`now - 3600`)
+const MIN_EPOCH_DIGITS: usize = 9;
+
+const MILLIS_PER_SECOND: i64 = 1000;
+const SECONDS_PER_MINUTE: i64 = 60;
+const SECONDS_PER_HOUR: i64 = 3600;
+const SECONDS_PER_DAY: i64 = 86_400;
+const SECONDS_PER_WEEK: i64 = 7 * SECONDS_PER_DAY;
+
+/// Result of evaluating a date expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// An instant in the configured zone, e.g. `now - 90m`
+    Instant(DateTime<FixedOffset>),
+    /// Elapsed milliseconds between two instants
+    Elapsed(i64),
+}
+
+/// Calendar months are kept apart from fixed lengths so `+ P1M` lands on the same day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Span {
+    months: i32,
+    millis: i64,
+}
+
+enum Operand {
+    Instant(DateTime<FixedOffset>),
+    Span(Span),
+}
+
+/// Evaluate `<instant> (± <duration>)*` or `<instant> - <instant> (± <duration>)*`.
+///
+/// Instants are `now`, epochs in seconds or milliseconds, and ISO 8601 dates;
+/// durations are compact (`3d`, `1h30m`, `2mo`) or ISO 8601 (`P1M`, `PT90M`).
+/// Operators must be separated by spaces so that dates keep their hyphens.
+pub fn evaluate(input: &str, now: DateTime<Utc>, zone: Zone, order: DateOrder) -> Option<Outcome> {
+    let input = input.trim();
+    if input.is_empty() || input.chars().count() > MAX_INPUT_CHARS {
+        return None;
+    }
+
+    let (first, rest) = split_terms(input)?;
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut value = Outcome::Instant(parse_instant(&first, now, zone, order)?);
+    for (negative, term) in rest {
+        let operand = match parse_span(&term) {
+            Some(span) => Operand::Span(span),
+            None => Operand::Instant(parse_instant(&term, now, zone, order)?),
+        };
+
+        value = match (value, operand) {
+            (Outcome::Instant(instant), Operand::Span(span)) => {
+                Outcome::Instant(shift(instant, span, negative, zone)?)
+            }
+            (Outcome::Instant(left), Operand::Instant(right)) if negative => {
+                Outcome::Elapsed((left - right).num_milliseconds())
+            }
+            // Elapsed time has no calendar to count months against
+            (Outcome::Elapsed(millis), Operand::Span(span)) if span.months == 0 => {
+                let delta = if negative { -span.millis } else { span.millis };
+                Outcome::Elapsed(millis.checked_add(delta)?)
+            }
+            _ => return None,
+        };
+    }
+
+    Some(value)
+}
+
+/// Split on standalone `+`/`-` tokens: `a b + c` -> (`a b`, [(false, `c`)])
+fn split_terms(input: &str) -> Option<(String, Vec<(bool, String)>)> {
+    let mut terms: Vec<(bool, Vec<&str>)> = vec![(false, Vec::new())];
+    for token in input.split_whitespace() {
+        match token {
+            "+" => terms.push((false, Vec::new())),
+            "-" => terms.push((true, Vec::new())),
+            _ => terms.last_mut()?.1.push(token),
+        }
+    }
+    if terms.iter().any(|(_, words)| words.is_empty()) {
+        return None;
+    }
+
+    let mut terms = terms
+        .into_iter()
+        .map(|(negative, words)| (negative, words.join(" ")));
+    let (_, first) = terms.next()?;
+    Some((first, terms.collect()))
+}
+
+fn parse_instant(
+    text: &str,
+    now: DateTime<Utc>,
+    zone: Zone,
+    order: DateOrder,
+) -> Option<DateTime<FixedOffset>> {
+    if text.eq_ignore_ascii_case("now") {
+        return Some(zone.from_utc(&now));
+    }
+
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        let value: i64 = text.parse().ok()?;
+        // Same length rule as plain clipboard epochs
+        let millis = if text.len() <= 10 {
+            value.checked_mul(MILLIS_PER_SECOND)?
+        } else {
+            value
+        };
+        return Some(zone.from_utc(&DateTime::from_timestamp_millis(millis)?));
+    }
+
+    if let Some(datetime) = parse_iso_with_offset(text) {
+        return Some(datetime);
+    }
+    literal::parse(text, order, zone)
+}
+
+/// RFC 3339 style dates that carry their own offset: `2024-01-01T00:00Z`, `2024-01-01 08:00+08:00`
+fn parse_iso_with_offset(text: &str) -> Option<DateTime<FixedOffset>> {
+    let normalized = match text.strip_suffix(['Z', 'z']) {
+        Some(head) => format!("{}+00:00", head),
+        None => text.to_string(),
+    };
+    [
+        "%Y-%m-%dT%H:%M:%S%.f%:z",
+        "%Y-%m-%dT%H:%M%:z",
+        "%Y-%m-%dT%H:%M:%S%.f%z",
+        "%Y-%m-%dT%H:%M%z",
+    ]
+    .iter()
+    .find_map(|format| DateTime::parse_from_str(&normalized.replacen(' ', "T", 1), format).ok())
+}
+
+fn parse_span(text: &str) -> Option<Span> {
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        if text.len() >= MIN_EPOCH_DIGITS {
+            return None;
+        }
+        let seconds: i64 = text.parse().ok()?;
+        return Some(Span {
+            months: 0,
+            millis: seconds.checked_mul(MILLIS_PER_SECOND)?,
+        });
+    }
+    parse_iso_span(text).or_else(|| parse_compact_span(text))
+}
+
+/// `P1Y2M3DT4H5M6S` and `P2W`, with years and months counted on the calendar
+fn parse_iso_span(text: &str) -> Option<Span> {
+    let rest = text.strip_prefix(['P', 'p'])?;
+    let (date_part, time_part) = match rest.split_once(['T', 't']) {
+        Some((date, time)) if !time.is_empty() => (date, time),
+        Some(_) => return None,
+        None => (rest, ""),
+    };
+    if date_part.is_empty() && time_part.is_empty() {
+        return None;
+    }
+
+    let mut span = Span::default();
+    for (value, unit) in split_components(date_part)? {
+        match unit {
+            "Y" | "y" => add_months(&mut span, value, 12)?,
+            "M" | "m" => add_months(&mut span, value, 1)?,
+            "W" | "w" => add_seconds(&mut span, value, SECONDS_PER_WEEK)?,
+            "D" | "d" => add_seconds(&mut span, value, SECONDS_PER_DAY)?,
+            _ => return None,
+        }
+    }
+    for (value, unit) in split_components(time_part)? {
+        match unit {
+            "H" | "h" => add_seconds(&mut span, value, SECONDS_PER_HOUR)?,
+            "M" | "m" => add_seconds(&mut span, value, SECONDS_PER_MINUTE)?,
+            "S" | "s" => add_seconds(&mut span, value, 1)?,
+            _ => return None,
+        }
+    }
+    Some(span)
+}
+
+/// `3d`, `90m`, `1h30m`, `2w`, `1mo`, `1y`; `m` is minutes as in Go durations
+fn parse_compact_span(text: &str) -> Option<Span> {
+    let mut span = Span::default();
+    for (value, unit) in split_components(&text.to_lowercase())? {
+        match unit {
+            "ms" => {
+                let millis = i64::try_from(value / NANOS_PER_SECOND).ok()?;
+                span.millis = span.millis.checked_add(millis)?;
+            }
+            "s" | "sec" | "secs" => add_seconds(&mut span, value, 1)?,
+            "m" | "min" | "mins" => add_seconds(&mut span, value, SECONDS_PER_MINUTE)?,
+            "h" | "hr" | "hrs" => add_seconds(&mut span, value, SECONDS_PER_HOUR)?,
+            "d" | "day" | "days" => add_seconds(&mut span, value, SECONDS_PER_DAY)?,
+            "w" | "wk" | "week" | "weeks" => add_seconds(&mut span, value, SECONDS_PER_WEEK)?,
+            "mo" | "month" | "months" => add_months(&mut span, value, 1)?,
+            "y" | "yr" | "year" | "years" => add_months(&mut span, value, 12)?,
+            _ => return None,
+        }
+    }
+    Some(span)
+}
+
+/// `value` is scaled by 10^9 as returned by `split_components`
+fn add_seconds(span: &mut Span, value: i128, seconds_per_unit: i64) -> Option<()> {
+    let nanos = value.checked_mul(seconds_per_unit as i128)?;
+    let millis = i64::try_from(nanos / NANOS_PER_MILLI).ok()?;
+    span.millis = span.millis.checked_add(millis)?;
+    Some(())
+}
+
+/// Calendar units must be whole numbers
+fn add_months(span: &mut Span, value: i128, months_per_unit: i32) -> Option<()> {
+    if value % NANOS_PER_SECOND != 0 {
+        return None;
+    }
+    let count = i32::try_from(value / NANOS_PER_SECOND).ok()?;
+    span.months = span
+        .months
+        .checked_add(count.checked_mul(months_per_unit)?)?;
+    Some(())
+}
+
+/// Apply calendar months on the wall clock in `zone`, then the fixed part
+fn shift(
+    instant: DateTime<FixedOffset>,
+    span: Span,
+    negative: bool,
+    zone: Zone,
+) -> Option<DateTime<FixedOffset>> {
+    let mut result = zone.from_utc(&instant.with_timezone(&Utc));
+    if span.months != 0 {
+        let months = Months::new(span.months.unsigned_abs());
+        let local = result.naive_local();
+        let shifted = if negative {
+            local.checked_sub_months(months)?
+        } else {
+            local.checked_add_months(months)?
+        };
+        result = zone.from_local(&shifted)?;
+    }
+    let millis = if negative { -span.millis } else { span.millis };
+    result.checked_add_signed(Duration::milliseconds(millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap()
+    }
+
+    fn eval(input: &str) -> Option<Outcome> {
+        evaluate(input, now(), Zone::Utc, DateOrder::Dmy)
+    }
+
+    fn instant(input: &str) -> String {
+        match eval(input) {
+            Some(Outcome::Instant(dt)) => dt.to_rfc3339(),
+            other => panic!("{} evaluated to {:?}", input, other),
+        }
+    }
+
+    #[test]
+    fn test_instant_plus_span() {
+        assert_eq!(instant("1704067200 + 3d"), "2024-01-04T00:00:00+00:00");
+        assert_eq!(instant("now - 90m"), "2024-03-15T10:30:00+00:00");
+        assert_eq!(
+            instant("2024-01-01T00:00Z + P1M"),
+            "2024-02-01T00:00:00+00:00"
+        );
+        assert_eq!(instant("2024-01-31 + 1mo"), "2024-02-29T00:00:00+00:00");
+        assert_eq!(
+            instant("1704067200000 + PT1H30M - 30m"),
+            "2024-01-01T01:00:00+00:00"
+        );
+        assert_eq!(instant("now - 3600"), "2024-03-15T11:00:00+00:00");
+        assert_eq!(instant("now + 1500ms"), "2024-03-15T12:00:01.500+00:00");
+    }
+
+    #[test]
+    fn test_difference() {
+        assert_eq!(
+            eval("2024-01-02T00:00Z - 2024-01-01T00:00Z"),
+            Some(Outcome::Elapsed(86_400_000))
+        );
+        assert_eq!(
+            eval("1704067200 - 1704060000 + 1s"),
+            Some(Outcome::Elapsed(7_201_000))
+        );
+        // Months have no fixed length
+        assert!(eval("1704067200 - 1704060000 + 1mo").is_none());
+    }
+
+    #[test]
+    fn test_named_zone_months() {
+        // Adding a month across the DST change keeps the wall time
+        let zone = Zone::parse("America/New_York").unwrap();
+        let result = evaluate("2024-03-01T12:00-05:00 + P1M", now(), zone, DateOrder::Mdy);
+        match result {
+            Some(Outcome::Instant(dt)) => assert_eq!(dt.to_rfc3339(), "2024-04-01T12:00:00-04:00"),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_reject_invalid() {
+        assert!(eval("now").is_none());
+        assert!(eval("1704067200").is_none());
+        assert!(eval("now + ").is_none());
+        assert!(eval("now + now").is_none());
+        assert!(eval("3d + now").is_none());
+        assert!(eval("now + 1.5mo").is_none());
+        assert!(eval("hello - world").is_none());
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::{DateTime, Datelike, FixedOffset, Weekday};
+use serde::Serialize;
+
+/// Calendar metadata for a decoded instant, computed in the configured zone
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CalendarInfo {
+    /// ISO weekday, 1 = Monday through 7 = Sunday
+    pub iso_weekday: u32,
+    /// Year the ISO week belongs to, which differs from the calendar year around New Year
+    pub iso_week_year: i32,
+    pub iso_week: u32,
+    /// Day of the year, starting at 1
+    pub ordinal: u32,
+    pub quarter: u32,
+    pub is_weekend: bool,
+}
+
+impl CalendarInfo {
+    pub fn new(datetime: &DateTime<FixedOffset>) -> Self {
+        let iso_week = datetime.iso_week();
+        let weekday = datetime.weekday();
+
+        Self {
+            iso_weekday: weekday.number_from_monday(),
+            iso_week_year: iso_week.year(),
+            iso_week: iso_week.week(),
+            ordinal: datetime.ordinal(),
+            quarter: datetime.month0() / 3 + 1,
+            is_weekend: matches!(weekday, Weekday::Sat | Weekday::Sun),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_new_year_iso_week() {
+        // 2021-01-01 is a Friday in ISO week 53 of 2020
+        let datetime = Utc
+            .with_ymd_and_hms(2021, 1, 1, 12, 0, 0)
+            .unwrap()
+            .fixed_offset();
+        let info = CalendarInfo::new(&datetime);
+        assert_eq!(info.iso_weekday, 5);
+        assert_eq!(info.iso_week_year, 2020);
+        assert_eq!(info.iso_week, 53);
+        assert_eq!(info.ordinal, 1);
+        assert_eq!(info.quarter, 1);
+        assert!(!info.is_weekend);
+    }
+
+    #[test]
+    fn test_weekend_and_quarter() {
+        // 2023-12-31 is a Sunday on the last day of Q4
+        let datetime = Utc
+            .with_ymd_and_hms(2023, 12, 31, 0, 0, 0)
+            .unwrap()
+            .fixed_offset();
+        let info = CalendarInfo::new(&datetime);
+        assert_eq!(info.iso_weekday, 7);
+        assert_eq!(info.ordinal, 365);
+        assert_eq!(info.quarter, 4);
+        assert!(info.is_weekend);
+    }
+}
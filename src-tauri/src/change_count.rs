@@ -0,0 +1,171 @@
+//! Platform clipboard change counters.
+//!
+//! macOS and Windows bump a counter every time something takes ownership of
+//! the clipboard, even when the new text is identical. Other platforms expose
+//! no such counter and fall back to comparing text, so re-copying identical
+//! text there goes unnoticed.
+
+use std::time::{Duration, Instant};
+
+/// The current clipboard change counter, if the platform has one
+#[cfg(target_os = "macos")]
+pub fn current() -> Option<u64> {
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+
+    unsafe {
+        let pasteboard: *mut AnyObject = msg_send![class!(NSPasteboard), generalPasteboard];
+        if pasteboard.is_null() {
+            return None;
+        }
+        let count: isize = msg_send![pasteboard, changeCount];
+        Some(count as u64)
+    }
+}
+
+/// The current clipboard change counter, if the platform has one
+#[cfg(target_os = "windows")]
+pub fn current() -> Option<u64> {
+    use windows::Win32::System::DataExchange::GetClipboardSequenceNumber;
+
+    // Zero means the calling process lacks access to the window station
+    match unsafe { GetClipboardSequenceNumber() } {
+        0 => None,
+        sequence => Some(sequence as u64),
+    }
+}
+
+/// The current clipboard change counter, if the platform has one
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn current() -> Option<u64> {
+    None
+}
+
+/// Decides whether a poll saw a fresh copy, including re-copies of identical text
+#[derive(Debug)]
+pub struct CopyDetector {
+    last_count: Option<u64>,
+    last_copy: Option<Instant>,
+}
+
+impl CopyDetector {
+    pub fn new(count: Option<u64>) -> Self {
+        Self {
+            last_count: count,
+            last_copy: None,
+        }
+    }
+
+    /// Record a poll. Changed text is always a fresh copy; identical text is
+    /// one when the change counter moved and `reshow_window` has passed since
+    /// the previous copy, since some apps bump the counter several times per copy.
+    /// Without a counter a re-copy can't be told apart from text left on the
+    /// clipboard, so identical text never counts.
+    pub fn observe(
+        &mut self,
+        text_changed: bool,
+        count: Option<u64>,
+        now: Instant,
+        reshow_window: Duration,
+    ) -> bool {
+        let count_changed =
+            matches!((self.last_count, count), (Some(last), Some(current)) if last != current);
+        self.last_count = count;
+
+        let fresh = text_changed
+            || (count_changed
+                && self
+                    .last_copy
+                    .is_none_or(|at| now.saturating_duration_since(at) >= reshow_window));
+        if fresh {
+            self.last_copy = Some(now);
+        }
+        fresh
+    }
+}
+
+/// Notices copies made while monitoring is paused from the change counter
+/// alone, so the clipboard is never read. Platforms without a counter report nothing.
+#[derive(Debug, Default)]
+pub struct PausedWatch {
+    last_count: Option<u64>,
+}
+
+impl PausedWatch {
+    /// Record a paused poll; true when the counter moved since the previous one
+    pub fn observe(&mut self, count: Option<u64>) -> bool {
+        let changed =
+            matches!((self.last_count, count), (Some(last), Some(current)) if last != current);
+        self.last_count = count;
+        changed
+    }
+
+    /// Forget the last count when monitoring resumes
+    pub fn reset(&mut self) {
+        self.last_count = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_millis(1000);
+
+    #[test]
+    fn test_text_change_is_fresh() {
+        let start = Instant::now();
+        let mut detector = CopyDetector::new(None);
+        assert!(detector.observe(true, None, start, WINDOW));
+        assert!(detector.observe(true, None, start + WINDOW / 4, WINDOW));
+    }
+
+    #[test]
+    fn test_identical_text_without_counter_never_refires() {
+        let start = Instant::now();
+        let mut detector = CopyDetector::new(None);
+        // Text already on the clipboard at startup is not a copy
+        assert!(!detector.observe(false, None, start, WINDOW));
+        assert!(detector.observe(true, None, start, WINDOW));
+        // Poll the same text like the monitor does, well past twice the window
+        let poll = Duration::from_millis(350);
+        let mut at = start + poll;
+        while at <= start + WINDOW * 3 {
+            assert!(!detector.observe(false, None, at, WINDOW));
+            at += poll;
+        }
+        // Different text in between makes the old text new again
+        assert!(detector.observe(true, None, at, WINDOW));
+        assert!(detector.observe(true, None, at + poll, WINDOW));
+    }
+
+    #[test]
+    fn test_recopy_with_counter() {
+        let start = Instant::now();
+        let mut detector = CopyDetector::new(Some(1));
+        assert!(detector.observe(true, Some(2), start, WINDOW));
+        assert!(!detector.observe(false, Some(2), start + WINDOW * 2, WINDOW));
+        assert!(detector.observe(false, Some(3), start + WINDOW * 3, WINDOW));
+    }
+
+    #[test]
+    fn test_counter_bumps_within_window_are_debounced() {
+        let start = Instant::now();
+        let mut detector = CopyDetector::new(Some(1));
+        assert!(detector.observe(true, Some(2), start, WINDOW));
+        assert!(!detector.observe(false, Some(3), start + WINDOW / 4, WINDOW));
+    }
+
+    #[test]
+    fn test_paused_watch() {
+        let mut watch = PausedWatch::default();
+        assert!(!watch.observe(Some(3)));
+        assert!(!watch.observe(Some(3)));
+        assert!(watch.observe(Some(4)));
+        watch.reset();
+        assert!(!watch.observe(Some(5)));
+        // Without a counter nothing is reported
+        assert!(!watch.observe(None));
+        assert!(!watch.observe(None));
+    }
+}
@@ -0,0 +1,694 @@
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::i18n::Language;
+use crate::zone::Zone;
+
+/// How far ahead to look for fire times; covers `0 0 29 2 *` across leap years
+const MAX_SEARCH_DAYS: i64 = 8 * 366;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+const WEEKDAY_NAMES_ZH: [&str; 7] = ["周日", "周一", "周二", "周三", "周四", "周五", "周六"];
+
+/// Explanation of a cron expression shown in the HUD
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CronInfo {
+    /// Plain-language schedule, e.g. "At 09:30, on Monday through Friday"
+    pub description: String,
+    /// Upcoming fire times formatted in the configured zone
+    pub next_runs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Second,
+    Minute,
+    Hour,
+    DayOfMonth,
+    Month,
+    DayOfWeek,
+}
+
+impl FieldKind {
+    /// Inclusive bounds accepted in the expression; day-of-week allows 7 for Sunday
+    fn bounds(self) -> (u32, u32) {
+        match self {
+            FieldKind::Second | FieldKind::Minute => (0, 59),
+            FieldKind::Hour => (0, 23),
+            FieldKind::DayOfMonth => (1, 31),
+            FieldKind::Month => (1, 12),
+            FieldKind::DayOfWeek => (0, 7),
+        }
+    }
+
+    fn parse_value(self, text: &str) -> Option<u32> {
+        let value = match text.parse::<u32>() {
+            Ok(value) => value,
+            Err(_) => {
+                let prefix = text.to_lowercase();
+                let names: &[&str] = match self {
+                    FieldKind::Month => &MONTH_NAMES,
+                    FieldKind::DayOfWeek => &WEEKDAY_NAMES,
+                    _ => return None,
+                };
+                let index = names.iter().position(|name| {
+                    prefix.len() == 3 && name.to_lowercase().starts_with(&prefix)
+                })?;
+                let first = if self == FieldKind::Month { 1 } else { 0 };
+                index as u32 + first
+            }
+        };
+        let (min, max) = self.bounds();
+        (min..=max).contains(&value).then_some(value)
+    }
+}
+
+/// One comma-separated item of a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    /// `*` or `*/step`
+    Any {
+        step: u32,
+    },
+    Value(u32),
+    /// `a-b`, `a-b/step`, or Quartz-style `a/step`
+    Range {
+        start: u32,
+        end: u32,
+        step: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Field {
+    kind: FieldKind,
+    parts: Vec<Part>,
+    /// Bit `n` is set when value `n` matches
+    mask: u64,
+}
+
+impl Field {
+    fn parse(text: &str, kind: FieldKind) -> Option<Self> {
+        let (min, max) = kind.bounds();
+        let mut parts = Vec::new();
+        let mut mask = 0u64;
+
+        for item in text.split(',') {
+            let (base, step) = match item.split_once('/') {
+                Some((base, step)) => (base, Some(step.parse::<u32>().ok().filter(|s| *s > 0)?)),
+                None => (item, None),
+            };
+
+            let part = if base == "*" || (base == "?" && is_day_field(kind)) {
+                Part::Any {
+                    step: step.unwrap_or(1),
+                }
+            } else if let Some((start, end)) = base.split_once('-') {
+                let start = kind.parse_value(start)?;
+                let end = kind.parse_value(end)?;
+                if start > end {
+                    return None;
+                }
+                Part::Range {
+                    start,
+                    end,
+                    step: step.unwrap_or(1),
+                }
+            } else {
+                let value = kind.parse_value(base)?;
+                match step {
+                    Some(step) => Part::Range {
+                        start: value,
+                        end: max,
+                        step,
+                    },
+                    None => Part::Value(value),
+                }
+            };
+
+            let (start, end, step) = match part {
+                Part::Any { step } => (min, max, step),
+                Part::Value(value) => (value, value, 1),
+                Part::Range { start, end, step } => (start, end, step),
+            };
+            for value in (start..=end).step_by(step as usize) {
+                // Sunday may be written as 0 or 7
+                let value = if kind == FieldKind::DayOfWeek {
+                    value % 7
+                } else {
+                    value
+                };
+                mask |= 1 << value;
+            }
+            parts.push(part);
+        }
+
+        Some(Self { kind, parts, mask })
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        self.mask & (1 << value) != 0
+    }
+
+    fn values(&self) -> impl Iterator<Item = u32> + '_ {
+        let (min, max) = self.kind.bounds();
+        (min..=max).filter(|value| self.matches(*value))
+    }
+
+    /// `*` and `?` without a step: the field places no restriction
+    fn is_every(&self) -> bool {
+        self.parts == [Part::Any { step: 1 }]
+    }
+
+    /// Vixie cron only ANDs day-of-month and day-of-week when one of them starts with `*`
+    fn starts_with_wildcard(&self) -> bool {
+        matches!(self.parts.first(), Some(Part::Any { .. }))
+    }
+
+    /// Plain values only, e.g. `0,30`
+    fn fixed_values(&self) -> Option<Vec<u32>> {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Value(value) => Some(*value),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn is_day_field(kind: FieldKind) -> bool {
+    matches!(kind, FieldKind::DayOfMonth | FieldKind::DayOfWeek)
+}
+
+/// A parsed 5-field (crontab, Kubernetes) or 6-field (with leading seconds) schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    seconds: Option<Field>,
+    minutes: Field,
+    hours: Field,
+    days_of_month: Field,
+    months: Field,
+    days_of_week: Field,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression or one of the `@daily`-style macros
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let expanded = match input.to_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ => input,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let (seconds, rest) = match fields.len() {
+            5 => (None, &fields[..]),
+            6 => (
+                Some(Field::parse(fields[0], FieldKind::Second)?),
+                &fields[1..],
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            seconds,
+            minutes: Field::parse(rest[0], FieldKind::Minute)?,
+            hours: Field::parse(rest[1], FieldKind::Hour)?,
+            days_of_month: Field::parse(rest[2], FieldKind::DayOfMonth)?,
+            months: Field::parse(rest[3], FieldKind::Month)?,
+            days_of_week: Field::parse(rest[4], FieldKind::DayOfWeek)?,
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if !self.months.matches(date.month()) {
+            return false;
+        }
+        let dom = self.days_of_month.matches(date.day());
+        let dow = self
+            .days_of_week
+            .matches(date.weekday().num_days_from_sunday());
+        if self.days_of_month.starts_with_wildcard() || self.days_of_week.starts_with_wildcard() {
+            dom && dow
+        } else {
+            dom || dow
+        }
+    }
+
+    /// The next `count` fire times strictly after `after`, as wall times in `zone`.
+    /// Wall times skipped by a DST change never fire.
+    pub fn upcoming(
+        &self,
+        after: DateTime<Utc>,
+        zone: Zone,
+        count: usize,
+    ) -> Vec<DateTime<FixedOffset>> {
+        let start = zone.from_utc(&after);
+        let seconds: Vec<u32> = match &self.seconds {
+            Some(field) => field.values().collect(),
+            None => vec![0],
+        };
+
+        let mut runs = Vec::with_capacity(count);
+        let mut date = start.date_naive();
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_day(date) {
+                for hour in self.hours.values() {
+                    for minute in self.minutes.values() {
+                        for second in &seconds {
+                            let Some(naive) = date.and_hms_opt(hour, minute, *second) else {
+                                continue;
+                            };
+                            let Some(run) = zone.from_local(&naive) else {
+                                continue;
+                            };
+                            if run <= start {
+                                continue;
+                            }
+                            runs.push(run);
+                            if runs.len() == count {
+                                return runs;
+                            }
+                        }
+                    }
+                }
+            }
+            date += Duration::days(1);
+        }
+        runs
+    }
+
+    /// Plain-language description of the schedule
+    pub fn describe(&self, language: Language) -> String {
+        match language {
+            Language::English => self.describe_english(),
+            Language::SimplifiedChinese => self.describe_chinese(),
+        }
+    }
+
+    /// `09:30` style clock times when seconds, minutes and hours are a few fixed values
+    fn clock_times(&self) -> Option<Vec<String>> {
+        let seconds = match &self.seconds {
+            Some(field) => field.fixed_values()?,
+            None => vec![0],
+        };
+        let minutes = self.minutes.fixed_values()?;
+        let hours = self.hours.fixed_values()?;
+        if seconds.len() * minutes.len() * hours.len() > 4 {
+            return None;
+        }
+
+        let mut times = Vec::new();
+        for hour in &hours {
+            for minute in &minutes {
+                for second in &seconds {
+                    times.push(if *second == 0 {
+                        format!("{:02}:{:02}", hour, minute)
+                    } else {
+                        format!("{:02}:{:02}:{:02}", hour, minute, second)
+                    });
+                }
+            }
+        }
+        Some(times)
+    }
+
+    fn describe_english(&self) -> String {
+        let mut segments = Vec::new();
+
+        if let Some(times) = self.clock_times() {
+            segments.push(format!("at {}", join_english(&times)));
+        } else {
+            let seconds = self
+                .seconds
+                .as_ref()
+                .filter(|field| field.parts != [Part::Value(0)]);
+            if let Some(field) = seconds {
+                segments.push(describe_english_field(field));
+            }
+            // "every second, every minute" says nothing the first part did not
+            if !(seconds.is_some() && self.minutes.is_every()) {
+                let mut minutes = describe_english_field(&self.minutes);
+                if self.hours.is_every() && self.minutes.fixed_values().is_some() {
+                    minutes.push_str(" of every hour");
+                }
+                segments.push(minutes);
+            }
+            if !self.hours.is_every() {
+                segments.push(describe_english_field(&self.hours));
+            }
+        }
+
+        match (self.days_of_month.is_every(), self.days_of_week.is_every()) {
+            (true, true) => {}
+            (false, true) => segments.push(describe_english_field(&self.days_of_month)),
+            (true, false) => segments.push(describe_english_field(&self.days_of_week)),
+            (false, false) => segments.push(format!(
+                "{} or {}",
+                describe_english_field(&self.days_of_month),
+                describe_english_field(&self.days_of_week)
+            )),
+        }
+        if !self.months.is_every() {
+            segments.push(describe_english_field(&self.months));
+        }
+
+        capitalize(&segments.join(", "))
+    }
+
+    fn describe_chinese(&self) -> String {
+        let mut segments = Vec::new();
+
+        if !self.months.is_every() {
+            segments.push(describe_chinese_field(&self.months));
+        }
+        let day = match (self.days_of_month.is_every(), self.days_of_week.is_every()) {
+            (true, true) => None,
+            (false, true) => Some(describe_chinese_field(&self.days_of_month)),
+            (true, false) => Some(describe_chinese_field(&self.days_of_week)),
+            (false, false) => Some(format!(
+                "{}或{}",
+                describe_chinese_field(&self.days_of_month),
+                describe_chinese_field(&self.days_of_week)
+            )),
+        };
+        if let Some(day) = day {
+            segments.push(day);
+        }
+
+        if let Some(times) = self.clock_times() {
+            if segments.is_empty() {
+                segments.push("每天".to_string());
+            }
+            segments.push(times.join("、"));
+        } else {
+            if !self.hours.is_every() {
+                segments.push(describe_chinese_field(&self.hours));
+            } else if self.minutes.fixed_values().is_some() {
+                segments.push("每小时".to_string());
+            }
+            let seconds = self
+                .seconds
+                .as_ref()
+                .filter(|field| field.parts != [Part::Value(0)]);
+            if !(seconds.is_some() && self.minutes.is_every()) {
+                segments.push(describe_chinese_field(&self.minutes));
+            }
+            if let Some(field) = seconds {
+                segments.push(describe_chinese_field(field));
+            }
+        }
+
+        segments.join(" ")
+    }
+}
+
+fn english_units(kind: FieldKind) -> (&'static str, &'static str) {
+    match kind {
+        FieldKind::Second => ("second", "seconds"),
+        FieldKind::Minute => ("minute", "minutes"),
+        FieldKind::Hour => ("hour", "hours"),
+        FieldKind::DayOfMonth | FieldKind::DayOfWeek => ("day", "days"),
+        FieldKind::Month => ("month", "months"),
+    }
+}
+
+fn english_value(kind: FieldKind, value: u32) -> String {
+    match kind {
+        FieldKind::Month => MONTH_NAMES[value as usize - 1].to_string(),
+        FieldKind::DayOfWeek => WEEKDAY_NAMES[value as usize % 7].to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// e.g. "every 5 minutes", "at minutes 0 and 30", "on days 1 through 15 of the month"
+fn describe_english_field(field: &Field) -> String {
+    let kind = field.kind;
+    let (singular, plural) = english_units(kind);
+
+    if let [Part::Any { step }] = field.parts[..] {
+        return if step == 1 {
+            format!("every {}", singular)
+        } else {
+            format!("every {} {}", step, plural)
+        };
+    }
+
+    let items: Vec<String> = field
+        .parts
+        .iter()
+        .map(|part| match *part {
+            Part::Any { step } => format!("every {} {}", step, plural),
+            Part::Value(value) => english_value(kind, value),
+            Part::Range {
+                start,
+                end,
+                step: 1,
+            } => format!(
+                "{} through {}",
+                english_value(kind, start),
+                english_value(kind, end)
+            ),
+            Part::Range { start, end, step } => format!(
+                "every {} {} from {} through {}",
+                step,
+                plural,
+                english_value(kind, start),
+                english_value(kind, end)
+            ),
+        })
+        .collect();
+    let list = join_english(&items);
+    let single = matches!(field.parts[..], [Part::Value(_)]);
+
+    match kind {
+        FieldKind::Second | FieldKind::Minute => {
+            format!("at {} {}", if single { singular } else { plural }, list)
+        }
+        FieldKind::Hour => format!("during {} {}", if single { singular } else { plural }, list),
+        FieldKind::DayOfMonth => format!(
+            "on {} {} of the month",
+            if single { singular } else { plural },
+            list
+        ),
+        FieldKind::Month => format!("in {}", list),
+        FieldKind::DayOfWeek => format!("on {}", list),
+    }
+}
+
+fn chinese_units(kind: FieldKind) -> (&'static str, &'static str) {
+    // (unit for "every N", suffix after a value)
+    match kind {
+        FieldKind::Second => ("秒", "秒"),
+        FieldKind::Minute => ("分钟", "分"),
+        FieldKind::Hour => ("小时", "点"),
+        FieldKind::DayOfMonth | FieldKind::DayOfWeek => ("天", "日"),
+        FieldKind::Month => ("个月", "月"),
+    }
+}
+
+fn chinese_value(kind: FieldKind, value: u32) -> String {
+    match kind {
+        FieldKind::DayOfWeek => WEEKDAY_NAMES_ZH[value as usize % 7].to_string(),
+        _ => format!("{}{}", value, chinese_units(kind).1),
+    }
+}
+
+/// e.g. "每5分钟", "0分、30分", "每月1日至15日", "每周一至周五"
+fn describe_chinese_field(field: &Field) -> String {
+    let kind = field.kind;
+    let (unit, _) = chinese_units(kind);
+
+    let items: Vec<String> = field
+        .parts
+        .iter()
+        .map(|part| match *part {
+            Part::Any { step: 1 } => format!("每{}", unit),
+            Part::Any { step } => format!("每{}{}", step, unit),
+            Part::Value(value) => chinese_value(kind, value),
+            Part::Range {
+                start,
+                end,
+                step: 1,
+            } => format!(
+                "{}至{}",
+                chinese_value(kind, start),
+                chinese_value(kind, end)
+            ),
+            Part::Range { start, end, step } => format!(
+                "{}至{}每{}{}",
+                chinese_value(kind, start),
+                chinese_value(kind, end),
+                step,
+                unit
+            ),
+        })
+        .collect();
+    let list = items.join("、");
+
+    if matches!(field.parts.first(), Some(Part::Any { .. })) {
+        return list;
+    }
+    match kind {
+        FieldKind::DayOfMonth => format!("每月{}", list),
+        FieldKind::DayOfWeek => format!("每{}", list),
+        _ => list,
+    }
+}
+
+/// "a", "a and b", "a, b and c"
+fn join_english(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [init @ .., last] => format!("{} and {}", init.join(", "), last),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn describe(input: &str, language: Language) -> String {
+        CronSchedule::parse(input).unwrap().describe(language)
+    }
+
+    #[test]
+    fn test_describe_english() {
+        assert_eq!(describe("* * * * *", Language::English), "Every minute");
+        assert_eq!(
+            describe("*/5 * * * *", Language::English),
+            "Every 5 minutes"
+        );
+        assert_eq!(
+            describe("0 * * * *", Language::English),
+            "At minute 0 of every hour"
+        );
+        assert_eq!(
+            describe("30 9 * * 1-5", Language::English),
+            "At 09:30, on Monday through Friday"
+        );
+        assert_eq!(
+            describe("0 0 1 jan *", Language::English),
+            "At 00:00, on day 1 of the month, in January"
+        );
+        assert_eq!(
+            describe("*/15 9-17 * * *", Language::English),
+            "Every 15 minutes, during hours 9 through 17"
+        );
+        assert_eq!(
+            describe("0 9,17 * * MON,FRI", Language::English),
+            "At 09:00 and 17:00, on Monday and Friday"
+        );
+        assert_eq!(
+            describe("*/10 * * * * *", Language::English),
+            "Every 10 seconds"
+        );
+        assert_eq!(describe("@daily", Language::English), "At 00:00");
+    }
+
+    #[test]
+    fn test_describe_chinese() {
+        assert_eq!(
+            describe("*/5 * * * *", Language::SimplifiedChinese),
+            "每5分钟"
+        );
+        assert_eq!(
+            describe("30 9 * * 1-5", Language::SimplifiedChinese),
+            "每周一至周五 09:30"
+        );
+        assert_eq!(
+            describe("0 0 1 * *", Language::SimplifiedChinese),
+            "每月1日 00:00"
+        );
+        assert_eq!(
+            describe("0 * * * *", Language::SimplifiedChinese),
+            "每小时 0分"
+        );
+        assert_eq!(
+            describe("0 8 * * *", Language::SimplifiedChinese),
+            "每天 08:00"
+        );
+    }
+
+    #[test]
+    fn test_upcoming_runs() {
+        // Monday 2024-01-01 10:00 UTC
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let schedule = CronSchedule::parse("30 9 * * 1-5").unwrap();
+        let runs = schedule.upcoming(now, Zone::Utc, 3);
+        let runs: Vec<String> = runs.iter().map(|run| run.to_rfc3339()).collect();
+        assert_eq!(
+            runs,
+            [
+                "2024-01-02T09:30:00+00:00",
+                "2024-01-03T09:30:00+00:00",
+                "2024-01-04T09:30:00+00:00"
+            ]
+        );
+
+        // 10:00 UTC is already 18:00 in Shanghai, so the next run is the following morning
+        let shanghai = Zone::parse("Asia/Shanghai").unwrap();
+        let run = schedule.upcoming(now, shanghai, 1)[0];
+        assert_eq!(run.to_rfc3339(), "2024-01-02T09:30:00+08:00");
+
+        // Day-of-month and day-of-week are ORed when both are restricted
+        let schedule = CronSchedule::parse("0 0 13 * 5").unwrap();
+        let runs = schedule.upcoming(now, Zone::Utc, 2);
+        assert_eq!(runs[0].to_rfc3339(), "2024-01-05T00:00:00+00:00");
+        assert_eq!(runs[1].to_rfc3339(), "2024-01-12T00:00:00+00:00");
+
+        let schedule = CronSchedule::parse("0 0 29 2 *").unwrap();
+        let runs = schedule.upcoming(now, Zone::Utc, 2);
+        assert_eq!(runs[1].to_rfc3339(), "2028-02-29T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_reject_invalid() {
+        assert!(CronSchedule::parse("1704067200").is_none());
+        assert!(CronSchedule::parse("* * * *").is_none());
+        assert!(CronSchedule::parse("60 * * * *").is_none());
+        assert!(CronSchedule::parse("*/0 * * * *").is_none());
+        assert!(CronSchedule::parse("5-1 * * * *").is_none());
+        assert!(CronSchedule::parse("0 0 L * *").is_none());
+        assert!(CronSchedule::parse("? * * * *").is_none());
+        assert!(CronSchedule::parse("@reboot").is_none());
+        assert!(CronSchedule::parse("the quick brown fox jumps").is_none());
+    }
+}
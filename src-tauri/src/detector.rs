@@ -0,0 +1,166 @@
+use crate::history::BUILT_IN_DETECTORS;
+use chrono::{DateTime, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Unit of the number captured by a custom detector
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeUnit {
+    #[default]
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl TimeUnit {
+    /// Convert a count of this unit to milliseconds, truncating sub-millisecond units
+    fn to_millis(self, value: i128) -> Option<i128> {
+        match self {
+            TimeUnit::Seconds => value.checked_mul(1000),
+            TimeUnit::Milliseconds => Some(value),
+            TimeUnit::Microseconds => Some(value / 1000),
+            TimeUnit::Nanoseconds => Some(value / 1_000_000),
+            TimeUnit::Minutes => value.checked_mul(60_000),
+            TimeUnit::Hours => value.checked_mul(3_600_000),
+            TimeUnit::Days => value.checked_mul(86_400_000),
+        }
+    }
+}
+
+/// A user-defined timestamp format such as `T+<seconds since 2015-01-01>`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomDetector {
+    /// Shown on the HUD when this detector decodes a copy
+    pub label: String,
+    /// Regex whose `value` group, or else first group, captures the number
+    pub pattern: String,
+    /// Instant the number counts from: RFC 3339, a `YYYY-MM-DD` date at UTC
+    /// midnight, or empty for the Unix epoch
+    pub origin: String,
+    pub unit: TimeUnit,
+}
+
+/// A detector ready to run against copied text
+#[derive(Debug, Clone)]
+pub struct CompiledDetector {
+    pub label: String,
+    regex: Regex,
+    origin_ms: i64,
+    unit: TimeUnit,
+}
+
+impl CustomDetector {
+    /// Validate the pattern and origin, returning a message naming the problem
+    pub fn compile(&self) -> Result<CompiledDetector, String> {
+        if BUILT_IN_DETECTORS.contains(&self.label.trim().to_lowercase().as_str()) {
+            return Err(format!(
+                "'{}' is the name of a built-in detector",
+                self.label
+            ));
+        }
+        let regex = crate::rules::compile(&self.pattern).map_err(|e| e.to_string())?;
+        if regex.captures_len() < 2 {
+            return Err("the pattern needs a capture group for the number".to_string());
+        }
+        let origin_ms = parse_origin(&self.origin).ok_or_else(|| {
+            format!(
+                "'{}' is not an RFC 3339 instant or YYYY-MM-DD date",
+                self.origin
+            )
+        })?;
+
+        Ok(CompiledDetector {
+            label: self.label.clone(),
+            regex,
+            origin_ms,
+            unit: self.unit,
+        })
+    }
+}
+
+fn parse_origin(origin: &str) -> Option<i64> {
+    let origin = origin.trim();
+    if origin.is_empty() {
+        return Some(0);
+    }
+    if let Ok(instant) = DateTime::parse_from_rfc3339(origin) {
+        return Some(instant.timestamp_millis());
+    }
+    let date = NaiveDate::parse_from_str(origin, "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis())
+}
+
+impl CompiledDetector {
+    /// The decoded instant as epoch milliseconds, if `text` matches
+    pub fn detect(&self, text: &str) -> Option<i64> {
+        let captures = self.regex.captures(text)?;
+        let value = captures.name("value").or_else(|| captures.get(1))?;
+        let count: i128 = value.as_str().parse().ok()?;
+        let millis = i128::from(self.origin_ms).checked_add(self.unit.to_millis(count)?)?;
+        i64::try_from(millis).ok()
+    }
+
+    /// Whether this detector keeps sub-second precision
+    pub fn is_milliseconds(&self) -> bool {
+        !matches!(
+            self.unit,
+            TimeUnit::Seconds | TimeUnit::Minutes | TimeUnit::Hours | TimeUnit::Days
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector(pattern: &str, origin: &str, unit: TimeUnit) -> CustomDetector {
+        CustomDetector {
+            label: "test".to_string(),
+            pattern: pattern.to_string(),
+            origin: origin.to_string(),
+            unit,
+        }
+    }
+
+    #[test]
+    fn test_custom_origin() {
+        let compiled = detector(r"^T\+(\d+)$", "2015-01-01", TimeUnit::Seconds)
+            .compile()
+            .unwrap();
+        // 2024-01-01T00:00:00Z is 283,996,800 seconds after 2015-01-01
+        assert_eq!(compiled.detect("T+283996800"), Some(1_704_067_200_000));
+        assert_eq!(compiled.detect("T-283996800"), None);
+    }
+
+    #[test]
+    fn test_named_group_and_unit() {
+        let compiled = detector(r"^(ev)-(?P<value>\d{13})-\w+$", "", TimeUnit::Milliseconds)
+            .compile()
+            .unwrap();
+        assert_eq!(
+            compiled.detect("ev-1704067200123-xyz"),
+            Some(1_704_067_200_123)
+        );
+        assert!(compiled.is_milliseconds());
+
+        let days = detector(r"day (\d+)", "1970-01-01T00:00:00Z", TimeUnit::Days)
+            .compile()
+            .unwrap();
+        assert_eq!(days.detect("day 19723"), Some(1_704_067_200_000));
+        assert_eq!(days.detect(&format!("day {}", "9".repeat(38))), None);
+    }
+
+    #[test]
+    fn test_invalid_detectors() {
+        assert!(detector("(", "", TimeUnit::Seconds).compile().is_err());
+        assert!(detector(r"\d+", "", TimeUnit::Seconds).compile().is_err());
+        assert!(detector(r"(\d+)", "yesterday", TimeUnit::Seconds)
+            .compile()
+            .is_err());
+    }
+}
@@ -0,0 +1,311 @@
+//! Format strings written in moment/Day.js, Java `DateTimeFormatter` or .NET
+//! custom date tokens, compiled into the same chrono items as strftime.
+
+use crate::output::{self, FormatError};
+use chrono::format::{Fixed, Item, StrftimeItems};
+use serde::{Deserialize, Serialize};
+
+/// Token syntax of the configured `time_format`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatDialect {
+    /// `%Y-%m-%d %H:%M:%S`
+    #[default]
+    Strftime,
+    /// `YYYY-MM-DD HH:mm:ss`
+    Moment,
+    /// `yyyy-MM-dd HH:mm:ss`
+    Java,
+    /// `yyyy-MM-dd HH:mm:ss`, with .NET quoting and `tt`/`fff`/`zzz`
+    Dotnet,
+}
+
+/// What one dialect token becomes
+enum Spec {
+    Strftime(&'static str),
+    Fixed(Fixed),
+}
+
+use Spec::Strftime as S;
+
+const MOMENT: &[(&str, Spec)] = &[
+    ("YYYY", S("%Y")),
+    ("YY", S("%y")),
+    ("Q", S("%q")),
+    ("M", S("%-m")),
+    ("MM", S("%m")),
+    ("MMM", S("%b")),
+    ("MMMM", S("%B")),
+    ("D", S("%-d")),
+    ("DD", S("%d")),
+    ("DDD", S("%-j")),
+    ("DDDD", S("%j")),
+    ("d", S("%w")),
+    ("ddd", S("%a")),
+    ("dddd", S("%A")),
+    ("E", S("%u")),
+    ("W", S("%-V")),
+    ("WW", S("%V")),
+    ("GGGG", S("%G")),
+    ("H", S("%-H")),
+    ("HH", S("%H")),
+    ("h", S("%-I")),
+    ("hh", S("%I")),
+    ("m", S("%-M")),
+    ("mm", S("%M")),
+    ("s", S("%-S")),
+    ("ss", S("%S")),
+    ("SSS", S("%3f")),
+    ("SSSSSS", S("%6f")),
+    ("SSSSSSSSS", S("%9f")),
+    ("A", S("%p")),
+    ("a", S("%P")),
+    ("Z", S("%:z")),
+    ("ZZ", S("%z")),
+    ("X", S("%s")),
+    ("x", S("%s%3f")),
+];
+
+const JAVA: &[(&str, Spec)] = &[
+    ("yyyy", S("%Y")),
+    ("uuuu", S("%Y")),
+    ("yy", S("%y")),
+    ("uu", S("%y")),
+    ("Q", S("%q")),
+    ("M", S("%-m")),
+    ("MM", S("%m")),
+    ("MMM", S("%b")),
+    ("MMMM", S("%B")),
+    ("L", S("%-m")),
+    ("LL", S("%m")),
+    ("d", S("%-d")),
+    ("dd", S("%d")),
+    ("D", S("%-j")),
+    ("DDD", S("%j")),
+    ("E", S("%a")),
+    ("EE", S("%a")),
+    ("EEE", S("%a")),
+    ("EEEE", S("%A")),
+    ("a", S("%p")),
+    ("H", S("%-H")),
+    ("HH", S("%H")),
+    ("h", S("%-I")),
+    ("hh", S("%I")),
+    ("m", S("%-M")),
+    ("mm", S("%M")),
+    ("s", S("%-S")),
+    ("ss", S("%S")),
+    ("SSS", S("%3f")),
+    ("SSSSSS", S("%6f")),
+    ("SSSSSSSSS", S("%9f")),
+    ("XX", Spec::Fixed(Fixed::TimezoneOffsetZ)),
+    ("XXX", Spec::Fixed(Fixed::TimezoneOffsetColonZ)),
+    ("xx", S("%z")),
+    ("xxx", S("%:z")),
+    ("Z", S("%z")),
+    // Instants are rendered at a fixed offset with no zone name, so the
+    // zone name token prints the offset even when an IANA zone is configured
+    ("z", S("%:z")),
+];
+
+const DOTNET: &[(&str, Spec)] = &[
+    ("yyyy", S("%Y")),
+    ("yy", S("%y")),
+    ("M", S("%-m")),
+    ("MM", S("%m")),
+    ("MMM", S("%b")),
+    ("MMMM", S("%B")),
+    ("d", S("%-d")),
+    ("dd", S("%d")),
+    ("ddd", S("%a")),
+    ("dddd", S("%A")),
+    ("H", S("%-H")),
+    ("HH", S("%H")),
+    ("h", S("%-I")),
+    ("hh", S("%I")),
+    ("m", S("%-M")),
+    ("mm", S("%M")),
+    ("s", S("%-S")),
+    ("ss", S("%S")),
+    ("fff", S("%3f")),
+    ("ffffff", S("%6f")),
+    ("tt", S("%p")),
+    ("zzz", S("%:z")),
+    ("K", Spec::Fixed(Fixed::TimezoneOffsetColonZ)),
+];
+
+/// Compile `pattern` into owned chrono items, naming the first unsupported token
+pub fn compile(pattern: &str, dialect: FormatDialect) -> Result<Vec<Item<'static>>, FormatError> {
+    let table = match dialect {
+        FormatDialect::Strftime => {
+            output::validate(pattern)?;
+            return Ok(owned_items(pattern));
+        }
+        FormatDialect::Moment => MOMENT,
+        FormatDialect::Java => JAVA,
+        FormatDialect::Dotnet => DOTNET,
+    };
+
+    let mut items = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let closing = match (dialect, c) {
+            (FormatDialect::Moment, '[') => Some(']'),
+            (FormatDialect::Java | FormatDialect::Dotnet, '\'') => Some('\''),
+            (FormatDialect::Dotnet, '"') => Some('"'),
+            _ => None,
+        };
+
+        if let Some(closing) = closing {
+            // Java writes a literal quote as `''`, both inside and outside quoted text
+            if dialect == FormatDialect::Java && chars.next_if(|&(_, n)| n == '\'').is_some() {
+                literal.push('\'');
+                continue;
+            }
+            loop {
+                match chars.next() {
+                    Some((_, n)) if n == closing => {
+                        if dialect == FormatDialect::Java
+                            && chars.next_if(|&(_, n)| n == '\'').is_some()
+                        {
+                            literal.push('\'');
+                            continue;
+                        }
+                        break;
+                    }
+                    Some((_, n)) => literal.push(n),
+                    None => {
+                        return Err(FormatError {
+                            token: pattern[start..].to_string(),
+                            position: start,
+                        })
+                    }
+                }
+            }
+        } else if dialect == FormatDialect::Dotnet && c == '\\' {
+            if let Some((_, escaped)) = chars.next() {
+                literal.push(escaped);
+            }
+        } else if dialect == FormatDialect::Dotnet && c == '%' {
+            // `%d` marks a lone single-letter token in .NET; the letter follows as usual
+        } else if c.is_ascii_alphabetic() {
+            let mut end = start + 1;
+            while chars.next_if(|&(_, n)| n == c).is_some() {
+                end += 1;
+            }
+            let token = &pattern[start..end];
+            let Some((_, spec)) = table.iter().find(|(name, _)| *name == token) else {
+                return Err(FormatError {
+                    token: token.to_string(),
+                    position: start,
+                });
+            };
+            flush_literal(&mut literal, &mut items);
+            match spec {
+                Spec::Strftime(strftime) => items.extend(owned_items(strftime)),
+                Spec::Fixed(fixed) => items.push(Item::Fixed(fixed.clone())),
+            }
+        } else {
+            literal.push(c);
+        }
+    }
+
+    flush_literal(&mut literal, &mut items);
+    Ok(items)
+}
+
+fn owned_items(strftime: &str) -> Vec<Item<'static>> {
+    StrftimeItems::new(strftime).map(Item::to_owned).collect()
+}
+
+fn flush_literal(literal: &mut String, items: &mut Vec<Item<'static>>) {
+    if !literal.is_empty() {
+        items.push(Item::OwnedLiteral(std::mem::take(literal).into_boxed_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, FixedOffset, TimeZone};
+
+    fn datetime(offset_hours: i32) -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(offset_hours * 3600)
+            .unwrap()
+            .timestamp_millis_opt(1_704_097_845_123 - offset_hours as i64 * 3_600_000)
+            .unwrap()
+    }
+
+    fn render(pattern: &str, dialect: FormatDialect, offset_hours: i32) -> String {
+        let items = compile(pattern, dialect).unwrap();
+        datetime(offset_hours)
+            .format_with_items(items.iter())
+            .to_string()
+    }
+
+    #[test]
+    fn test_strftime_is_unchanged() {
+        assert_eq!(
+            render("%Y-%m-%d %H:%M:%S", FormatDialect::Strftime, 8),
+            "2024-01-01 08:30:45"
+        );
+        assert_eq!(
+            compile("%Y %Q", FormatDialect::Strftime).unwrap_err().token,
+            "%Q"
+        );
+    }
+
+    #[test]
+    fn test_moment() {
+        assert_eq!(
+            render("YYYY-MM-DD HH:mm:ss.SSS Z", FormatDialect::Moment, 8),
+            "2024-01-01 08:30:45.123 +08:00"
+        );
+        assert_eq!(
+            render("ddd, MMM D [at] h:mm A", FormatDialect::Moment, 0),
+            "Mon, Jan 1 at 8:30 AM"
+        );
+        // Percent signs are plain text outside strftime
+        assert_eq!(render("100% YY", FormatDialect::Moment, 0), "100% 24");
+    }
+
+    #[test]
+    fn test_java() {
+        assert_eq!(
+            render("yyyy-MM-dd'T'HH:mm:ssXXX", FormatDialect::Java, 0),
+            "2024-01-01T08:30:45Z"
+        );
+        assert_eq!(
+            render("EEEE d MMMM, hh 'o''clock' a", FormatDialect::Java, 0),
+            "Monday 1 January, 08 o'clock AM"
+        );
+        // Zone names are not available, only offsets
+        assert_eq!(render("HH:mm z", FormatDialect::Java, 8), "08:30 +08:00");
+    }
+
+    #[test]
+    fn test_dotnet() {
+        assert_eq!(
+            render("yyyy/MM/dd HH:mm:ss.fff zzz", FormatDialect::Dotnet, -5),
+            "2024/01/01 08:30:45.123 -05:00"
+        );
+        assert_eq!(
+            render("\"Day\" d \\a\\t h tt", FormatDialect::Dotnet, 0),
+            "Day 1 at 8 AM"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_tokens() {
+        let error = compile("YYYY-MM-DD Do", FormatDialect::Moment).unwrap_err();
+        assert_eq!((error.token.as_str(), error.position), ("o", 12));
+
+        let error = compile("yyyy-MM-dd VV", FormatDialect::Java).unwrap_err();
+        assert_eq!((error.token.as_str(), error.position), ("VV", 11));
+
+        let error = compile("yyyy 'unterminated", FormatDialect::Java).unwrap_err();
+        assert_eq!(error.position, 5);
+    }
+}
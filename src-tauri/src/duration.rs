@@ -0,0 +1,325 @@
+use crate::i18n::Language;
+use serde::Serialize;
+
+/// Nominal lengths used for calendar units in ISO 8601 durations
+const SECONDS_PER_MINUTE: i128 = 60;
+const SECONDS_PER_HOUR: i128 = 3600;
+const SECONDS_PER_DAY: i128 = 86_400;
+const SECONDS_PER_WEEK: i128 = 7 * SECONDS_PER_DAY;
+const SECONDS_PER_MONTH: i128 = 30 * SECONDS_PER_DAY;
+const SECONDS_PER_YEAR: i128 = 365 * SECONDS_PER_DAY;
+
+pub(crate) const NANOS_PER_SECOND: i128 = 1_000_000_000;
+pub(crate) const NANOS_PER_MILLI: i128 = 1_000_000;
+
+/// Decoded duration shown in the HUD
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DurationInfo {
+    /// Normalized human form, e.g. "1 day 0 h 0 min" or "1天0小时0分钟"
+    pub human: String,
+    pub total_seconds: i64,
+    pub total_milliseconds: i64,
+}
+
+/// Parse an ISO 8601 duration (`PT1H30M`), a Go duration (`1h30m0s`)
+/// or a raw count of seconds (`86400`), described in `language`
+pub fn parse_duration(input: &str, language: Language) -> Option<DurationInfo> {
+    let nanos = parse_raw_seconds(input)
+        .or_else(|| parse_iso8601(input))
+        .or_else(|| parse_go(input))?;
+
+    let total_seconds = i64::try_from(nanos / NANOS_PER_SECOND).ok()?;
+    let total_milliseconds = i64::try_from(nanos / NANOS_PER_MILLI).ok()?;
+
+    Some(DurationInfo {
+        human: format_human(nanos, language),
+        total_seconds,
+        total_milliseconds,
+    })
+}
+
+/// Describe an elapsed number of milliseconds, e.g. the difference of two instants
+pub(crate) fn from_millis(millis: i64, language: Language) -> DurationInfo {
+    DurationInfo {
+        human: format_human(millis as i128 * NANOS_PER_MILLI, language),
+        total_seconds: millis / 1000,
+        total_milliseconds: millis,
+    }
+}
+
+/// Raw integers are interpreted as seconds
+fn parse_raw_seconds(input: &str) -> Option<i128> {
+    if input.is_empty() || !input.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let seconds: i64 = input.parse().ok()?;
+    Some(seconds as i128 * NANOS_PER_SECOND)
+}
+
+/// ISO 8601 durations: `[-]PnYnMnWnDTnHnMnS`, the last component may carry a fraction.
+/// Years and months use nominal lengths of 365 and 30 days.
+fn parse_iso8601(input: &str) -> Option<i128> {
+    let (negative, rest) = split_sign(input);
+    let rest = rest.strip_prefix('P').or_else(|| rest.strip_prefix('p'))?;
+    if rest.is_empty() {
+        return None;
+    }
+
+    let (date_part, time_part) = match rest.find(['T', 't']) {
+        Some(idx) => (&rest[..idx], Some(&rest[idx + 1..])),
+        None => (rest, None),
+    };
+
+    let mut total: i128 = 0;
+    let mut components = 0;
+
+    for (value, unit) in split_components(date_part)? {
+        let scale = match unit {
+            "Y" | "y" => SECONDS_PER_YEAR,
+            "M" | "m" => SECONDS_PER_MONTH,
+            "W" | "w" => SECONDS_PER_WEEK,
+            "D" | "d" => SECONDS_PER_DAY,
+            _ => return None,
+        };
+        total = total.checked_add(value.checked_mul(scale)?)?;
+        components += 1;
+    }
+
+    if let Some(time_part) = time_part {
+        // A time designator must be followed by at least one component
+        if time_part.is_empty() {
+            return None;
+        }
+        for (value, unit) in split_components(time_part)? {
+            let scale = match unit {
+                "H" | "h" => SECONDS_PER_HOUR,
+                "M" | "m" => SECONDS_PER_MINUTE,
+                "S" | "s" => 1,
+                _ => return None,
+            };
+            total = total.checked_add(value.checked_mul(scale)?)?;
+            components += 1;
+        }
+    }
+
+    if components == 0 {
+        return None;
+    }
+
+    Some(if negative { -total } else { total })
+}
+
+/// Go durations as printed by `time.Duration.String()`: `[-]1h30m0s`, `1.5s`, `300ms`
+fn parse_go(input: &str) -> Option<i128> {
+    let (negative, rest) = split_sign(input);
+    if rest.is_empty() {
+        return None;
+    }
+    // Go prints a zero duration as "0s" but also accepts a bare "0"
+    if rest == "0" {
+        return Some(0);
+    }
+
+    let mut total: i128 = 0;
+    for (value, unit) in split_components(rest)? {
+        let nanos_per_unit = match unit {
+            "ns" => 1,
+            "us" | "µs" | "μs" => 1_000,
+            "ms" => NANOS_PER_MILLI,
+            "s" => NANOS_PER_SECOND,
+            "m" => SECONDS_PER_MINUTE * NANOS_PER_SECOND,
+            "h" => SECONDS_PER_HOUR * NANOS_PER_SECOND,
+            _ => return None,
+        };
+        total = total.checked_add(value.checked_mul(nanos_per_unit)? / NANOS_PER_SECOND)?;
+    }
+
+    Some(if negative { -total } else { total })
+}
+
+fn split_sign(input: &str) -> (bool, &str) {
+    if let Some(rest) = input.strip_prefix('-') {
+        (true, rest)
+    } else {
+        (false, input.strip_prefix('+').unwrap_or(input))
+    }
+}
+
+/// Split `1h30m0.5s` into `(value, unit)` pairs. Values are scaled by
+/// `NANOS_PER_SECOND` so that fractional components stay exact.
+pub(crate) fn split_components(input: &str) -> Option<Vec<(i128, &str)>> {
+    let mut components = Vec::new();
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+            .unwrap_or(rest.len());
+        if number_len == 0 {
+            return None;
+        }
+        let (number, after) = rest.split_at(number_len);
+
+        let unit_len = after
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(after.len());
+        if unit_len == 0 {
+            return None;
+        }
+        let (unit, after) = after.split_at(unit_len);
+
+        components.push((parse_scaled_decimal(number)?, unit));
+        rest = after;
+    }
+
+    Some(components)
+}
+
+/// Parse `12.345` (or `12,345`) into `12_345_000_000`, i.e. scaled by 10^9
+fn parse_scaled_decimal(number: &str) -> Option<i128> {
+    let (int_part, frac_part) = match number.find(['.', ',']) {
+        Some(idx) => (&number[..idx], &number[idx + 1..]),
+        None => (number, ""),
+    };
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+    if frac_part.len() > 9 || !frac_part.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let int_value: i128 = if int_part.is_empty() {
+        0
+    } else {
+        int_part.parse().ok()?
+    };
+    let frac_value: i128 = if frac_part.is_empty() {
+        0
+    } else {
+        format!("{:0<9}", frac_part).parse().ok()?
+    };
+
+    int_value
+        .checked_mul(NANOS_PER_SECOND)?
+        .checked_add(frac_value)
+}
+
+/// Render a duration from its largest non-zero unit down to minutes,
+/// adding seconds only when they are non-zero: `1 day 0 h 0 min`, `1 h 30 min`, `45 s`
+fn format_human(nanos: i128, language: Language) -> String {
+    let sign = if nanos < 0 { "-" } else { "" };
+    let nanos = nanos.abs();
+
+    let total_seconds = nanos / NANOS_PER_SECOND;
+    let millis = (nanos % NANOS_PER_SECOND) / NANOS_PER_MILLI;
+
+    let days = total_seconds / SECONDS_PER_DAY;
+    let hours = (total_seconds % SECONDS_PER_DAY) / SECONDS_PER_HOUR;
+    let minutes = (total_seconds % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE;
+    let seconds = total_seconds % SECONDS_PER_MINUTE;
+
+    let unit = |count: String, english: &str, chinese: &str| match language {
+        Language::English => format!("{} {}", count, english),
+        Language::SimplifiedChinese => format!("{}{}", count, chinese),
+    };
+
+    let mut parts = Vec::new();
+    if days > 0 {
+        let english = if days == 1 { "day" } else { "days" };
+        parts.push(unit(days.to_string(), english, "天"));
+    }
+    if days > 0 || hours > 0 {
+        parts.push(unit(hours.to_string(), "h", "小时"));
+    }
+    if days > 0 || hours > 0 || minutes > 0 {
+        parts.push(unit(minutes.to_string(), "min", "分钟"));
+    }
+    if seconds > 0 || millis > 0 || parts.is_empty() {
+        let count = if millis > 0 {
+            let fraction = format!("{:03}", millis);
+            format!("{}.{}", seconds, fraction.trim_end_matches('0'))
+        } else {
+            seconds.to_string()
+        };
+        parts.push(unit(count, "s", "秒"));
+    }
+
+    // Chinese runs the units together without spaces
+    let separator = match language {
+        Language::English => " ",
+        Language::SimplifiedChinese => "",
+    };
+    format!("{}{}", sign, parts.join(separator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_duration_en(input: &str) -> Option<DurationInfo> {
+        parse_duration(input, Language::English)
+    }
+
+    #[test]
+    fn test_parse_iso8601() {
+        let info = parse_duration_en("PT1H30M").unwrap();
+        assert_eq!(info.total_seconds, 5400);
+        assert_eq!(info.human, "1 h 30 min");
+
+        let info = parse_duration_en("P3DT4H").unwrap();
+        assert_eq!(info.total_seconds, 3 * 86_400 + 4 * 3600);
+        assert_eq!(info.human, "3 days 4 h 0 min");
+
+        let info = parse_duration_en("PT0.5S").unwrap();
+        assert_eq!(info.total_milliseconds, 500);
+        assert_eq!(info.human, "0.5 s");
+    }
+
+    #[test]
+    fn test_parse_go() {
+        let info = parse_duration_en("1h30m0s").unwrap();
+        assert_eq!(info.total_seconds, 5400);
+        assert_eq!(info.total_milliseconds, 5_400_000);
+
+        let info = parse_duration_en("-1.5s").unwrap();
+        assert_eq!(info.total_milliseconds, -1500);
+        assert_eq!(info.human, "-1.5 s");
+
+        assert_eq!(parse_duration_en("300ms").unwrap().total_milliseconds, 300);
+    }
+
+    #[test]
+    fn test_parse_raw_seconds() {
+        let info = parse_duration_en("86400").unwrap();
+        assert_eq!(info.total_seconds, 86_400);
+        assert_eq!(info.total_milliseconds, 86_400_000);
+        assert_eq!(info.human, "1 day 0 h 0 min");
+    }
+
+    #[test]
+    fn test_chinese() {
+        let zh = |input| {
+            parse_duration(input, Language::SimplifiedChinese)
+                .unwrap()
+                .human
+        };
+        assert_eq!(zh("PT1H30M"), "1小时30分钟");
+        assert_eq!(zh("P3DT4H"), "3天4小时0分钟");
+        assert_eq!(zh("-1.5s"), "-1.5秒");
+        assert_eq!(
+            from_millis(45_000, Language::SimplifiedChinese).human,
+            "45秒"
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid() {
+        assert!(parse_duration_en("").is_none());
+        assert!(parse_duration_en("P").is_none());
+        assert!(parse_duration_en("PT").is_none());
+        assert!(parse_duration_en("P1H").is_none());
+        assert!(parse_duration_en("1x").is_none());
+        assert!(parse_duration_en("hello").is_none());
+        assert!(parse_duration_en("h1").is_none());
+    }
+}
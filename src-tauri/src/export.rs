@@ -0,0 +1,177 @@
+//! History exports for attaching to tickets and incident reports.
+
+use crate::history::HistoryEntry;
+use crate::zone::Zone;
+use chrono::{DateTime, SecondsFormat};
+use serde::Deserialize;
+
+/// File format of a history export
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    JsonLines,
+    Markdown,
+}
+
+const COLUMNS: [&str; 6] = [
+    "detected_at",
+    "raw_value",
+    "interpretation",
+    "formatted_time",
+    "timestamp_milliseconds",
+    "pinned",
+];
+
+impl ExportFormat {
+    /// File name extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Markdown => "md",
+        }
+    }
+
+    /// Render entries in this format. CSV and Markdown show `detected_at` in
+    /// `zone`; JSON Lines keeps the stored entry as is.
+    pub fn render(self, entries: &[HistoryEntry], zone: Zone) -> String {
+        match self {
+            ExportFormat::Csv => {
+                let mut out = format!("{}\r\n", COLUMNS.join(","));
+                for entry in entries {
+                    let row: Vec<String> =
+                        cells(entry, zone).iter().map(|c| csv_field(c)).collect();
+                    out.push_str(&row.join(","));
+                    out.push_str("\r\n");
+                }
+                out
+            }
+            ExportFormat::JsonLines => entries
+                .iter()
+                .filter_map(|entry| serde_json::to_string(entry).ok())
+                .map(|line| line + "\n")
+                .collect(),
+            ExportFormat::Markdown => {
+                let mut out = format!("| {} |\n", COLUMNS.join(" | "));
+                out.push_str(&format!("|{}\n", " --- |".repeat(COLUMNS.len())));
+                for entry in entries {
+                    let row: Vec<String> = cells(entry, zone)
+                        .iter()
+                        .map(|c| markdown_cell(c))
+                        .collect();
+                    out.push_str(&format!("| {} |\n", row.join(" | ")));
+                }
+                out
+            }
+        }
+    }
+}
+
+fn cells(entry: &HistoryEntry, zone: Zone) -> [String; 6] {
+    let detected_at = DateTime::from_timestamp_millis(entry.detected_at)
+        .map(|utc| {
+            zone.from_utc(&utc)
+                .to_rfc3339_opts(SecondsFormat::Secs, true)
+        })
+        .unwrap_or_default();
+    [
+        detected_at,
+        entry.raw_value.clone(),
+        entry.interpretation.detector().to_string(),
+        entry.formatted_time.clone(),
+        entry.timestamp_milliseconds.to_string(),
+        entry.pinned.to_string(),
+    ]
+}
+
+/// Quote a CSV field (RFC 4180) and defuse leading characters spreadsheets
+/// would run as a formula
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) && value.parse::<f64>().is_err() {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Keep a value on one table row
+fn markdown_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::Interpretation;
+
+    fn entries() -> Vec<HistoryEntry> {
+        vec![
+            HistoryEntry {
+                id: 2,
+                raw_value: "30 9 * * 1-5".to_string(),
+                interpretation: Interpretation::Cron,
+                formatted_time: "At 09:30, Monday through Friday".to_string(),
+                timestamp_milliseconds: 0,
+                detected_at: 1_704_067_260_000,
+                pinned: true,
+            },
+            HistoryEntry {
+                id: 1,
+                raw_value: "a | \"b\"\nc".to_string(),
+                interpretation: Interpretation::Timestamp {
+                    milliseconds: false,
+                },
+                formatted_time: "2024-01-01 00:00:00".to_string(),
+                timestamp_milliseconds: 1_704_067_200_000,
+                detected_at: 1_704_067_200_000,
+                pinned: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_csv() {
+        let zone = Zone::parse("+08:00").unwrap();
+        assert_eq!(
+            ExportFormat::Csv.render(&entries(), zone),
+            "detected_at,raw_value,interpretation,formatted_time,timestamp_milliseconds,pinned\r\n\
+             2024-01-01T08:01:00+08:00,30 9 * * 1-5,cron,\"At 09:30, Monday through Friday\",0,true\r\n\
+             2024-01-01T08:00:00+08:00,\"a | \"\"b\"\"\nc\",timestamp,2024-01-01 00:00:00,1704067200000,false\r\n"
+        );
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("-5"), "-5");
+    }
+
+    #[test]
+    fn test_markdown() {
+        let table = ExportFormat::Markdown.render(&entries(), Zone::Utc);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1], "| --- | --- | --- | --- | --- | --- |");
+        assert_eq!(
+            lines[3],
+            "| 2024-01-01T00:00:00Z | a \\| \"b\" c | timestamp | 2024-01-01 00:00:00 | 1704067200000 | false |"
+        );
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let lines = ExportFormat::JsonLines.render(&entries(), Zone::Utc);
+        let parsed: Vec<HistoryEntry> = lines
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(parsed, entries());
+    }
+}
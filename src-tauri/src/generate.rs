@@ -0,0 +1,47 @@
+/// An entry of the tray's "Copy current time" submenu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NowFormat {
+    /// The user's `time_format`
+    Configured,
+    /// The output format with this name
+    Output(String),
+}
+
+/// Id prefix of output formats; the name follows
+const OUTPUT_PREFIX: &str = "output:";
+
+impl NowFormat {
+    /// Stable identifier used in menu item ids
+    pub fn id(&self) -> String {
+        match self {
+            NowFormat::Configured => "configured".to_string(),
+            NowFormat::Output(name) => format!("{}{}", OUTPUT_PREFIX, name),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        if id == "configured" {
+            return Some(NowFormat::Configured);
+        }
+        id.strip_prefix(OUTPUT_PREFIX)
+            .map(|name| NowFormat::Output(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_round_trip() {
+        for format in [
+            NowFormat::Configured,
+            NowFormat::Output("ISO 8601".to_string()),
+            NowFormat::Output("configured".to_string()),
+            NowFormat::Output("a:b".to_string()),
+        ] {
+            assert_eq!(NowFormat::from_id(&format.id()), Some(format));
+        }
+        assert_eq!(NowFormat::from_id("seconds"), None);
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::FixedOffset;
+use serde::Serialize;
+
+/// An epoch with the UTC offset it was recorded in, as written by git
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitDate {
+    pub seconds: i64,
+    pub offset: FixedOffset,
+    /// `Name <email>` from an `author`/`committer`/`tagger` line
+    pub identity: Option<String>,
+}
+
+/// The instant as recorded by its author, shown next to the configured zone
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct AuthorTime {
+    /// Formatted with `time_format` in the author's offset
+    pub formatted_time: String,
+    /// The author's offset, e.g. `+08:00`
+    pub offset: String,
+    pub identity: Option<String>,
+}
+
+/// Parse git's raw date format `1704067200 +0800`, or a signature line such as
+/// `author Name <email> 1704067200 +0800` from `git cat-file -p`
+pub fn parse(input: &str) -> Option<GitDate> {
+    let input = input.trim();
+    let (head, offset) = input.rsplit_once(' ')?;
+    let offset = parse_raw_offset(offset)?;
+    let (identity, epoch) = match head.trim_end().rsplit_once(' ') {
+        Some((prefix, epoch)) => (Some(parse_signature(prefix)?), epoch),
+        None => (None, head),
+    };
+
+    if epoch.is_empty() || epoch.len() > 10 || !epoch.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(GitDate {
+        seconds: epoch.parse().ok()?,
+        offset,
+        identity,
+    })
+}
+
+/// `author Name <email>` -> `Name <email>`
+fn parse_signature(prefix: &str) -> Option<String> {
+    let identity = ["author ", "committer ", "tagger "]
+        .iter()
+        .find_map(|keyword| prefix.strip_prefix(keyword))?
+        .trim();
+    if !identity.ends_with('>') || !identity.contains('<') {
+        return None;
+    }
+    Some(identity.to_string())
+}
+
+/// Git writes offsets as exactly `±HHMM`
+fn parse_raw_offset(text: &str) -> Option<FixedOffset> {
+    let sign = match text.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits = &text[1..];
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_date() {
+        let date = parse("1704067200 +0800").unwrap();
+        assert_eq!(date.seconds, 1704067200);
+        assert_eq!(date.offset.local_minus_utc(), 8 * 3600);
+        assert_eq!(date.identity, None);
+
+        let date = parse("1704067200 -0530").unwrap();
+        assert_eq!(date.offset.local_minus_utc(), -(5 * 3600 + 30 * 60));
+    }
+
+    #[test]
+    fn test_signature_line() {
+        let date = parse("author Jane Doe <jane@example.com> 1704067200 +0800").unwrap();
+        assert_eq!(date.seconds, 1704067200);
+        assert_eq!(
+            date.identity.as_deref(),
+            Some("Jane Doe <jane@example.com>")
+        );
+
+        let date = parse("committer A <a@b> 1704067200 +0000").unwrap();
+        assert_eq!(date.identity.as_deref(), Some("A <a@b>"));
+    }
+
+    #[test]
+    fn test_reject_invalid() {
+        assert!(parse("1704067200").is_none());
+        assert!(parse("1704067200 0800").is_none());
+        assert!(parse("1704067200 +08:00").is_none());
+        assert!(parse("1704067200 +0870").is_none());
+        assert!(parse("hello 1704067200 +0800").is_none());
+        assert!(parse("author Jane 1704067200 +0800").is_none());
+    }
+}
@@ -0,0 +1,418 @@
+//! Recently decoded copies, newest first, persisted in `history.json`.
+
+use crate::{HudPayload, PayloadKind};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use tauri::AppHandle;
+
+/// Store file holding the history
+const HISTORY_STORE: &str = "history.json";
+const ENTRIES_KEY: &str = "entries";
+
+/// Longest raw value shown in a menu label before it is cut with an ellipsis
+const LABEL_RAW_CHARS: usize = 24;
+
+/// What a copied value was decoded as
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Interpretation {
+    /// An instant, e.g. an epoch number, date literal or natural-language date
+    Timestamp {
+        milliseconds: bool,
+    },
+    GitDate,
+    /// Decoded by the custom detector with this label
+    Custom {
+        detector: String,
+    },
+    Duration,
+    Cron,
+}
+
+/// Names of the built-in detectors, which custom detectors may not take
+pub const BUILT_IN_DETECTORS: [&str; 4] = ["timestamp", "git_date", "duration", "cron"];
+
+impl Interpretation {
+    /// The name a history query filters by: the type such as `timestamp` or
+    /// `git_date`, or `custom:<label>` for a custom detector
+    pub fn detector(&self) -> Cow<'_, str> {
+        match self {
+            Interpretation::Timestamp { .. } => Cow::Borrowed("timestamp"),
+            Interpretation::GitDate => Cow::Borrowed("git_date"),
+            Interpretation::Custom { detector } => Cow::Owned(format!("custom:{}", detector)),
+            Interpretation::Duration => Cow::Borrowed("duration"),
+            Interpretation::Cron => Cow::Borrowed("cron"),
+        }
+    }
+}
+
+/// One decoded copy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub raw_value: String,
+    pub interpretation: Interpretation,
+    pub formatted_time: String,
+    pub timestamp_milliseconds: i64,
+    /// When the copy was decoded, as epoch milliseconds
+    pub detected_at: i64,
+    /// Pinned entries are kept when the history is trimmed
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl HistoryEntry {
+    /// Record a HUD payload; the id is assigned when it joins a `History`
+    pub fn from_payload(payload: &HudPayload, detected_at: i64) -> Self {
+        let interpretation = match payload.kind {
+            PayloadKind::Duration => Interpretation::Duration,
+            PayloadKind::Cron => Interpretation::Cron,
+            PayloadKind::Timestamp => match (&payload.detector, &payload.author_time) {
+                (Some(detector), _) => Interpretation::Custom {
+                    detector: detector.clone(),
+                },
+                (None, Some(_)) => Interpretation::GitDate,
+                (None, None) => Interpretation::Timestamp {
+                    milliseconds: payload.is_milliseconds,
+                },
+            },
+        };
+        Self {
+            id: 0,
+            raw_value: payload.raw_value.clone(),
+            interpretation,
+            formatted_time: payload.formatted_time.clone(),
+            timestamp_milliseconds: payload.timestamp_milliseconds,
+            detected_at,
+            pinned: false,
+        }
+    }
+
+    /// Text a tray click copies: the formatted time of an instant, or the
+    /// raw value of a duration or cron entry, whose formatted time is prose
+    pub fn copy_text(&self) -> &str {
+        match self.interpretation {
+            Interpretation::Duration | Interpretation::Cron => &self.raw_value,
+            _ => &self.formatted_time,
+        }
+    }
+
+    /// Single-line tray label such as `1704067200 → 2024-01-01 00:00:00`,
+    /// marked when pinned
+    pub fn menu_label(&self) -> String {
+        let raw = self
+            .raw_value
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let raw = if raw.chars().count() > LABEL_RAW_CHARS {
+            let cut: String = raw.chars().take(LABEL_RAW_CHARS - 1).collect();
+            format!("{}…", cut)
+        } else {
+            raw
+        };
+        let pin = if self.pinned { "📌 " } else { "" };
+        format!("{}{} → {}", pin, raw, self.formatted_time)
+    }
+}
+
+/// Filter for `query_history`; empty fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    /// Case-insensitive text to find in the raw value or formatted time
+    pub text: String,
+    /// Earliest `detected_at`, as epoch milliseconds
+    pub from: Option<i64>,
+    /// Latest `detected_at`, as epoch milliseconds
+    pub to: Option<i64>,
+    /// See `Interpretation::detector`
+    pub detector: Option<String>,
+    pub pinned_only: bool,
+}
+
+impl HistoryQuery {
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        let text = self.text.trim().to_lowercase();
+        let text_matches = text.is_empty()
+            || entry.raw_value.to_lowercase().contains(&text)
+            || entry.formatted_time.to_lowercase().contains(&text);
+
+        text_matches
+            && self.from.is_none_or(|from| entry.detected_at >= from)
+            && self.to.is_none_or(|to| entry.detected_at <= to)
+            && self
+                .detector
+                .as_deref()
+                .is_none_or(|detector| entry.interpretation.detector() == detector)
+            && (!self.pinned_only || entry.pinned)
+    }
+}
+
+/// Bounded list of decoded copies, newest first
+#[derive(Debug, Default)]
+pub struct History {
+    entries: VecDeque<HistoryEntry>,
+    next_id: u64,
+}
+
+impl History {
+    /// Restore persisted entries, keeping the newest `limit`
+    pub fn new(entries: Vec<HistoryEntry>, limit: usize) -> Self {
+        let next_id = entries.iter().map(|e| e.id).max().map_or(1, |id| id + 1);
+        let mut history = Self {
+            entries: entries.into(),
+            next_id,
+        };
+        history.trim(limit);
+        history
+    }
+
+    /// Add a decoded copy at the front. Copying the same text again moves its
+    /// entry to the front, still pinned, instead of adding a duplicate.
+    pub fn record(&mut self, mut entry: HistoryEntry, limit: usize) {
+        entry.pinned |= self
+            .entries
+            .iter()
+            .any(|e| e.pinned && e.raw_value == entry.raw_value);
+        self.entries.retain(|e| e.raw_value != entry.raw_value);
+        entry.id = self.next_id;
+        self.next_id += 1;
+        self.entries.push_front(entry);
+        self.trim(limit);
+    }
+
+    /// Drop the oldest unpinned entries beyond `limit`. Pinned entries do
+    /// not count towards the limit.
+    pub fn trim(&mut self, limit: usize) {
+        let mut unpinned = 0;
+        self.entries.retain(|e| {
+            if e.pinned {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= limit
+        });
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    /// Pin or unpin an entry, returning `false` if there is no such entry
+    pub fn set_pinned(&mut self, id: u64, pinned: bool) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                entry.pinned = pinned;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Entries matching `query`, newest first
+    pub fn query(&self, query: &HistoryQuery) -> Vec<HistoryEntry> {
+        self.entries
+            .iter()
+            .filter(|e| query.matches(e))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Read the persisted entries, or none if the store is missing or unreadable
+pub fn load(app: &AppHandle) -> Vec<HistoryEntry> {
+    use tauri_plugin_store::StoreExt;
+
+    let stored = match app.store(HISTORY_STORE) {
+        Ok(store) => store.get(ENTRIES_KEY),
+        Err(e) => {
+            error!("Failed to open history store: {}", e);
+            return Vec::new();
+        }
+    };
+    stored
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Write the history to its store
+pub fn save(app: &AppHandle, history: &History) -> Result<(), String> {
+    use tauri_plugin_store::StoreExt;
+
+    let entries: Vec<&HistoryEntry> = history.entries().collect();
+    let value = serde_json::to_value(entries).map_err(|e| e.to_string())?;
+    let store = app.store(HISTORY_STORE).map_err(|e| e.to_string())?;
+    store.set(ENTRIES_KEY, value);
+    store.save().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(raw: &str) -> HistoryEntry {
+        HistoryEntry {
+            id: 0,
+            raw_value: raw.to_string(),
+            interpretation: Interpretation::Timestamp {
+                milliseconds: false,
+            },
+            formatted_time: "2024-01-01 00:00:00".to_string(),
+            timestamp_milliseconds: 1_704_067_200_000,
+            detected_at: 1_704_067_200_000,
+            pinned: false,
+        }
+    }
+
+    fn raw_values(history: &History) -> Vec<&str> {
+        history.entries().map(|e| e.raw_value.as_str()).collect()
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_newest_first() {
+        let mut history = History::new(Vec::new(), 3);
+        for raw in ["1", "2", "3", "4"] {
+            history.record(entry(raw), 3);
+        }
+        assert_eq!(raw_values(&history), ["4", "3", "2"]);
+
+        history.record(entry("3"), 3);
+        assert_eq!(raw_values(&history), ["3", "4", "2"]);
+        assert_eq!(history.get(5).map(|e| e.raw_value.as_str()), Some("3"));
+        assert!(history.get(3).is_none());
+    }
+
+    #[test]
+    fn test_ids_continue_after_restore() {
+        let mut restored = entry("1704067200");
+        restored.id = 7;
+        let mut history = History::new(vec![restored], 10);
+        history.record(entry("1704067201"), 10);
+        assert_eq!(history.entries().next().map(|e| e.id), Some(8));
+
+        assert_eq!(
+            History::new(vec![entry("a"), entry("b")], 1)
+                .entries()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_pinned_entries_survive_trimming() {
+        let mut history = History::new(Vec::new(), 2);
+        history.record(entry("expiry"), 2);
+        let id = history.entries().next().unwrap().id;
+        assert!(history.set_pinned(id, true));
+        for raw in ["1", "2", "3"] {
+            history.record(entry(raw), 2);
+        }
+        assert_eq!(raw_values(&history), ["3", "2", "expiry"]);
+
+        // Copying a pinned value again keeps it pinned
+        history.record(entry("expiry"), 2);
+        assert_eq!(raw_values(&history), ["expiry", "3", "2"]);
+        assert!(history.entries().next().unwrap().pinned);
+        assert!(!history.set_pinned(999, true));
+    }
+
+    #[test]
+    fn test_query() {
+        let mut history = History::new(Vec::new(), 10);
+        let mut yesterday = entry("eyJhbGciOi exp 1704067200");
+        yesterday.detected_at = 1_000;
+        history.record(yesterday, 10);
+        let mut cron = entry("30 9 * * 1-5");
+        cron.interpretation = Interpretation::Cron;
+        cron.formatted_time = "At 09:30 on weekdays".to_string();
+        cron.detected_at = 2_000;
+        history.record(cron, 10);
+
+        let found = |query: HistoryQuery| -> Vec<String> {
+            history
+                .query(&query)
+                .into_iter()
+                .map(|e| e.raw_value)
+                .collect()
+        };
+        assert_eq!(found(HistoryQuery::default()).len(), 2);
+        assert_eq!(
+            found(HistoryQuery {
+                text: "EXP".to_string(),
+                ..Default::default()
+            }),
+            ["eyJhbGciOi exp 1704067200"]
+        );
+        assert_eq!(
+            found(HistoryQuery {
+                text: "weekdays".to_string(),
+                ..Default::default()
+            }),
+            ["30 9 * * 1-5"]
+        );
+        assert_eq!(
+            found(HistoryQuery {
+                from: Some(1_500),
+                ..Default::default()
+            }),
+            ["30 9 * * 1-5"]
+        );
+        assert_eq!(
+            found(HistoryQuery {
+                to: Some(1_000),
+                detector: Some("timestamp".to_string()),
+                ..Default::default()
+            }),
+            ["eyJhbGciOi exp 1704067200"]
+        );
+        assert!(found(HistoryQuery {
+            pinned_only: true,
+            ..Default::default()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn test_menu_label() {
+        assert_eq!(
+            entry("1704067200").menu_label(),
+            "1704067200 → 2024-01-01 00:00:00"
+        );
+        let long = entry("author A U Thor <author@example.com> 1704067200 +0800").menu_label();
+        assert!(long.starts_with("author A U Thor <author… → "));
+    }
+
+    #[test]
+    fn test_copy_text() {
+        assert_eq!(entry("1704067200").copy_text(), "2024-01-01 00:00:00");
+        let mut cron = entry("30 9 * * 1-5");
+        cron.interpretation = Interpretation::Cron;
+        cron.formatted_time = "At 09:30, Monday through Friday".to_string();
+        assert_eq!(cron.copy_text(), "30 9 * * 1-5");
+    }
+
+    #[test]
+    fn test_entry_round_trip() {
+        let mut custom = entry("T+283996800");
+        custom.interpretation = Interpretation::Custom {
+            detector: "mission".to_string(),
+        };
+        let json = serde_json::to_value(&custom).unwrap();
+        assert_eq!(
+            json["interpretation"],
+            serde_json::json!({ "type": "custom", "detector": "mission" })
+        );
+        assert_eq!(
+            serde_json::from_value::<HistoryEntry>(json).unwrap(),
+            custom
+        );
+        assert_eq!(custom.interpretation.detector(), "custom:mission");
+    }
+}
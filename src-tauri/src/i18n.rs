@@ -0,0 +1,28 @@
+/// Languages the app ships translations for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    SimplifiedChinese,
+}
+
+impl Language {
+    /// Map a BCP 47 locale such as `zh-CN` or `en-US` to a shipped language
+    pub fn from_locale(locale: &str) -> Self {
+        if locale.to_lowercase().starts_with("zh") {
+            Language::SimplifiedChinese
+        } else {
+            Language::English
+        }
+    }
+
+    /// Language of the current system locale
+    pub fn system() -> Self {
+        Self::from_locale(&system_locale())
+    }
+}
+
+/// The system locale as a BCP 47 tag, defaulting to `en-US`
+pub fn system_locale() -> String {
+    sys_locale::get_locale().unwrap_or_else(|| "en-US".to_string())
+}
@@ -0,0 +1,234 @@
+//! RFC 5545 iCalendar export of a decoded instant.
+
+use crate::zone::Zone;
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Content lines longer than this many octets are folded (RFC 5545 §3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+const PRODID: &str = "-//Timesdump//Timesdump//EN";
+
+/// An event at a decoded instant, as requested by the HUD
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub title: String,
+    pub timestamp_milliseconds: i64,
+    /// Remind this many minutes before the event, if set
+    pub alarm_minutes: Option<u32>,
+    /// Extra notes, e.g. the copied text the instant came from
+    #[serde(default)]
+    pub description: String,
+}
+
+/// An event handed from the HUD to the editor in the settings window, which
+/// unlike the non-activating HUD can take keyboard focus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventDraft {
+    pub event: CalendarEvent,
+    /// The instant as the HUD showed it
+    pub formatted_time: String,
+}
+
+impl CalendarEvent {
+    /// Render a VCALENDAR with one zero-length VEVENT. The start is written in
+    /// `zone`, with a VTIMEZONE describing its offset at that instant.
+    /// `None` if the timestamp is outside chrono's range.
+    pub fn to_ics(&self, zone: Zone, now: DateTime<Utc>) -> Option<String> {
+        let utc = DateTime::from_timestamp_millis(self.timestamp_milliseconds)?;
+        let start = zone.from_utc(&utc);
+
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:{}", PRODID),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+        ];
+        let dtstart = match tzid(zone, &start) {
+            Some(tzid) => {
+                let offset = utc_offset(start.offset());
+                lines.extend([
+                    "BEGIN:VTIMEZONE".to_string(),
+                    format!("TZID:{}", tzid),
+                    "BEGIN:STANDARD".to_string(),
+                    "DTSTART:19700101T000000".to_string(),
+                    format!("TZOFFSETFROM:{}", offset),
+                    format!("TZOFFSETTO:{}", offset),
+                    "END:STANDARD".to_string(),
+                    "END:VTIMEZONE".to_string(),
+                ]);
+                format!("DTSTART;TZID={}:{}", tzid, start.format("%Y%m%dT%H%M%S"))
+            }
+            None => format!("DTSTART:{}", utc.format("%Y%m%dT%H%M%SZ")),
+        };
+
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!(
+                "UID:{}-{}@timesdump",
+                self.timestamp_milliseconds,
+                now.timestamp_millis()
+            ),
+            format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+            dtstart,
+            format!("SUMMARY:{}", escape_text(self.title.trim())),
+        ]);
+        if !self.description.trim().is_empty() {
+            lines.push(format!(
+                "DESCRIPTION:{}",
+                escape_text(self.description.trim())
+            ));
+        }
+        if let Some(minutes) = self.alarm_minutes {
+            lines.extend([
+                "BEGIN:VALARM".to_string(),
+                "ACTION:DISPLAY".to_string(),
+                format!("DESCRIPTION:{}", escape_text(self.title.trim())),
+                format!("TRIGGER:-PT{}M", minutes),
+                "END:VALARM".to_string(),
+            ]);
+        }
+        lines.extend(["END:VEVENT".to_string(), "END:VCALENDAR".to_string()]);
+
+        Some(lines.iter().map(|line| fold(line)).collect())
+    }
+
+    /// A file name derived from the title, e.g. `Cert expiry.ics`
+    pub fn file_name(&self) -> String {
+        let stem: String = self
+            .title
+            .trim()
+            .chars()
+            .filter(|c| !matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+            .filter(|c| !c.is_control())
+            .take(60)
+            .collect();
+        let stem = stem.trim();
+        format!("{}.ics", if stem.is_empty() { "event" } else { stem })
+    }
+}
+
+/// TZID for the zone, or `None` when the start is written in UTC
+fn tzid(zone: Zone, start: &DateTime<FixedOffset>) -> Option<String> {
+    match zone {
+        Zone::Utc => None,
+        Zone::Named(tz) => Some(tz.name().to_string()),
+        // No IANA name to refer to, so name the offset itself; a colon is not
+        // allowed in an unquoted parameter value
+        Zone::Local | Zone::Fixed(_) => Some(format!("UTC{}", utc_offset(start.offset()))),
+    }
+}
+
+/// `+0800` form used by TZOFFSETFROM and TZOFFSETTO
+fn utc_offset(offset: &FixedOffset) -> String {
+    let seconds = offset.local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.unsigned_abs() / 60;
+    format!("{}{:02}{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// Escape a TEXT value (RFC 5545 §3.3.11)
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+/// Fold a content line at 75 octets without splitting a character, ending it with CRLF
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + 8);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            // The leading space counts towards the next line
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(alarm_minutes: Option<u32>) -> CalendarEvent {
+        CalendarEvent {
+            title: "Cert expiry; api, edge".to_string(),
+            timestamp_milliseconds: 1_704_067_200_000,
+            alarm_minutes,
+            description: "Decoded from 1704067200".to_string(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 12, 1, 9, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_utc_event() {
+        let ics = event(None).to_ics(Zone::Utc, now()).unwrap();
+        assert_eq!(
+            ics,
+            "BEGIN:VCALENDAR\r\n\
+             VERSION:2.0\r\n\
+             PRODID:-//Timesdump//Timesdump//EN\r\n\
+             CALSCALE:GREGORIAN\r\n\
+             METHOD:PUBLISH\r\n\
+             BEGIN:VEVENT\r\n\
+             UID:1704067200000-1701421200000@timesdump\r\n\
+             DTSTAMP:20231201T090000Z\r\n\
+             DTSTART:20240101T000000Z\r\n\
+             SUMMARY:Cert expiry\\; api\\, edge\r\n\
+             DESCRIPTION:Decoded from 1704067200\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n"
+        );
+    }
+
+    #[test]
+    fn test_zone_and_alarm() {
+        let zone = Zone::parse("Asia/Kolkata").unwrap();
+        let ics = event(Some(15)).to_ics(zone, now()).unwrap();
+        assert!(ics.contains("TZID:Asia/Kolkata\r\n"));
+        assert!(ics.contains("TZOFFSETTO:+0530\r\n"));
+        assert!(ics.contains("DTSTART;TZID=Asia/Kolkata:20240101T053000\r\n"));
+        assert!(ics.contains("BEGIN:VALARM\r\nACTION:DISPLAY\r\n"));
+        assert!(ics.contains("TRIGGER:-PT15M\r\n"));
+
+        let fixed = event(None)
+            .to_ics(Zone::parse("-03:30").unwrap(), now())
+            .unwrap();
+        assert!(fixed.contains("DTSTART;TZID=UTC-0330:20231231T203000\r\n"));
+        assert!(fixed.contains("TZID:UTC-0330\r\n"));
+        assert!(fixed.contains("TZOFFSETFROM:-0330\r\n"));
+    }
+
+    #[test]
+    fn test_long_lines_are_folded() {
+        let mut long = event(None);
+        long.title = "证书到期".repeat(10);
+        let ics = long.to_ics(Zone::Utc, now()).unwrap();
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_OCTETS, "{:?}", line);
+        }
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("SUMMARY:{}\r\n", "证书到期".repeat(10))));
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(event(None).file_name(), "Cert expiry; api, edge.ics");
+        let mut untitled = event(None);
+        untitled.title = " /: ".to_string();
+        assert_eq!(untitled.file_name(), "event.ics");
+    }
+}
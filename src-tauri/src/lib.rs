@@ -1,17 +1,64 @@
 use arboard::Clipboard;
-use chrono::{Datelike, TimeZone, Utc};
-use log::{debug, error, info};
+use chrono::format::Item;
+use chrono::{DateTime, Datelike, FixedOffset, Utc};
+use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 
+mod alt_calendar;
+mod arith;
+mod calendar;
+mod change_count;
+mod cron;
+mod detector;
+mod dialect;
+mod duration;
+mod export;
+mod generate;
 mod ghost_window;
+mod gitdate;
+mod history;
+mod i18n;
+mod ical;
+mod literal;
+mod localize;
+mod natural;
+mod output;
+mod range;
+mod rejection;
+mod relative;
+mod rules;
+mod snippet;
+mod suppress;
 mod tray;
+mod zone;
+
+pub use alt_calendar::CalendarSystem;
+pub use calendar::CalendarInfo;
+pub use cron::CronInfo;
+pub use detector::{CustomDetector, TimeUnit};
+pub use dialect::FormatDialect;
+pub use duration::DurationInfo;
+pub use export::ExportFormat;
+pub use generate::NowFormat;
+pub use gitdate::AuthorTime;
+pub use history::{HistoryEntry, HistoryQuery, Interpretation};
+pub use i18n::{system_locale, Language};
+pub use ical::{CalendarEvent, EventDraft};
+pub use literal::DateOrder;
+pub use localize::FormatLocale;
+pub use output::{FormatError, FormattedVariant, OutputFormat};
+pub use range::{RangeRejection, RecentWindow};
+pub use rejection::{ContentShape, Rejection, RejectionSummary};
+pub use rules::{ContentRule, RuleHits, RuleStats};
+pub use snippet::{CodeSnippet, CodeTarget};
+pub use zone::Zone;
 
 pub use ghost_window::setup_ghost_window;
-pub use tray::setup_tray_menu;
+pub use tray::{refresh_copy_now_menu, setup_tray_menu};
 
 /// HUD popup position on screen
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -27,14 +74,55 @@ pub enum HudPosition {
 }
 
 /// Configuration for timestamp parsing
+///
+/// Missing keys fall back to their defaults so that settings saved by older
+/// versions keep loading.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TimestampConfig {
     pub min_year: i32,
     pub max_year: i32,
+    /// Optional window around now, checked together with the year range
+    pub recent_window: Option<RecentWindow>,
     pub display_duration_ms: u64,
     pub time_format: String,
-    #[serde(default)]
+    /// Token syntax of `time_format`: strftime, moment, Java or .NET
+    pub format_dialect: FormatDialect,
+    /// Locale for month and weekday names: `auto` follows the system, or a BCP 47 tag such as `zh-CN`
+    pub format_locale: String,
+    /// Named formats offered as "copy as" choices, in display order
+    pub output_formats: Vec<OutputFormat>,
     pub hud_position: HudPosition,
+    /// After re-copying identical text, how long before it can show the HUD again
+    pub reshow_window_ms: u64,
+    /// Zone used to render instants: `UTC`, `Local`, an IANA name or a `±HH:MM` offset
+    pub timezone: String,
+    /// Decode copied date literals such as `2024年1月1日` or `01/02/2024`
+    pub date_literals: bool,
+    /// How to read ambiguous numeric dates such as `01/02/2024`
+    pub date_order: DateOrder,
+    /// Secondary calendar shown next to the Gregorian date
+    pub calendar_system: CalendarSystem,
+    /// Decode durations (`PT1H30M`, `1h30m0s`, raw second counts) that are not timestamps
+    pub duration_mode: bool,
+    /// Resolve copied date expressions such as "tomorrow 9am" or "明天下午3点"
+    pub natural_language: bool,
+    /// Evaluate copied date arithmetic such as `now - 90m` or `1704067200 + 3d`
+    pub date_math: bool,
+    /// Replace a copied timestamp in the clipboard with its formatted time
+    pub auto_replace: bool,
+    /// Regexes for copied text that must never be decoded, e.g. account IDs
+    pub ignore_rules: Vec<ContentRule>,
+    /// When non-empty, only copied text matching one of these is decoded
+    pub allow_rules: Vec<ContentRule>,
+    /// User-defined formats such as `T+<seconds since 2015-01-01>`
+    pub custom_detectors: Vec<CustomDetector>,
+    /// Explain copied cron expressions such as `30 9 * * 1-5`
+    pub cron_mode: bool,
+    /// How many upcoming fire times to list for a cron expression
+    pub cron_runs: usize,
+    /// How many decoded copies to keep in the history; 0 turns it off
+    pub history_limit: usize,
 }
 
 impl Default for TimestampConfig {
@@ -42,33 +130,316 @@ impl Default for TimestampConfig {
         Self {
             min_year: 1990,
             max_year: 2050,
+            recent_window: None,
             display_duration_ms: 5000,
             time_format: "%Y-%m-%d %H:%M:%S".to_string(),
+            format_dialect: FormatDialect::default(),
+            format_locale: "auto".to_string(),
+            output_formats: output::default_formats(),
             hud_position: HudPosition::default(),
+            reshow_window_ms: 1000,
+            timezone: "UTC".to_string(),
+            date_literals: false,
+            date_order: DateOrder::default(),
+            calendar_system: CalendarSystem::default(),
+            duration_mode: false,
+            natural_language: false,
+            date_math: false,
+            auto_replace: false,
+            ignore_rules: Vec::new(),
+            allow_rules: Vec::new(),
+            custom_detectors: Vec::new(),
+            cron_mode: false,
+            cron_runs: 5,
+            history_limit: 100,
+        }
+    }
+}
+
+/// Why `save_settings` refused a config
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SettingsError {
+    /// A strftime pattern contains a specifier chrono cannot format
+    InvalidFormat {
+        /// `time_format` or `output_formats[<index>]`
+        field: String,
+        token: String,
+        position: usize,
+    },
+    /// An ignore or allow rule is not a valid regex
+    InvalidRule {
+        /// `ignore_rules[<index>]` or `allow_rules[<index>]`
+        field: String,
+        message: String,
+    },
+    /// A custom detector has a bad pattern or origin
+    InvalidDetector {
+        /// `custom_detectors[<index>]`
+        field: String,
+        message: String,
+    },
+    /// The settings store could not be written
+    Storage { message: String },
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::InvalidFormat {
+                field,
+                token,
+                position,
+            } => write!(
+                f,
+                "{}: unsupported specifier '{}' at position {}",
+                field, token, position
+            ),
+            SettingsError::InvalidRule { field, message }
+            | SettingsError::InvalidDetector { field, message } => {
+                write!(f, "{}: {}", field, message)
+            }
+            SettingsError::Storage { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl TimestampConfig {
+    /// Check the format patterns, which would otherwise panic when formatted
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        let invalid = |field: String, error: FormatError| SettingsError::InvalidFormat {
+            field,
+            token: error.token,
+            position: error.position,
+        };
+        self.time_items()
+            .map_err(|e| invalid("time_format".to_string(), e))?;
+        for (index, format) in self.output_formats.iter().enumerate() {
+            output::validate(&format.pattern)
+                .map_err(|e| invalid(format!("output_formats[{}]", index), e))?;
+        }
+        for (field, rules) in [
+            ("ignore_rules", &self.ignore_rules),
+            ("allow_rules", &self.allow_rules),
+        ] {
+            for (index, rule) in rules.iter().enumerate() {
+                rules::compile(&rule.pattern).map_err(|e| SettingsError::InvalidRule {
+                    field: format!("{}[{}]", field, index),
+                    message: e.to_string(),
+                })?;
+            }
+        }
+        for (index, detector) in self.custom_detectors.iter().enumerate() {
+            detector
+                .compile()
+                .map_err(|message| SettingsError::InvalidDetector {
+                    field: format!("custom_detectors[{}]", index),
+                    message,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Defaults for a first run, with the locale's usual date order and clock
+    pub fn for_locale(locale: &str) -> Self {
+        Self {
+            time_format: localize::default_time_format(locale),
+            ..Self::default()
+        }
+    }
+
+    /// Locale used for month and weekday names
+    pub fn names_locale(&self, system_locale: &str) -> FormatLocale {
+        match self.format_locale.trim() {
+            "" | "auto" => FormatLocale::from_tag(system_locale),
+            tag => FormatLocale::from_tag(tag),
+        }
+    }
+
+    /// `time_format` compiled from its dialect into chrono items
+    pub fn time_items(&self) -> Result<Vec<Item<'static>>, FormatError> {
+        dialect::compile(&self.time_format, self.format_dialect)
+    }
+
+    /// Build a config from persisted key/value entries.
+    ///
+    /// Each entry is applied on its own, so one malformed value only resets
+    /// that setting instead of discarding the whole config.
+    pub fn from_entries(entries: impl IntoIterator<Item = (String, serde_json::Value)>) -> Self {
+        let defaults = Self::default();
+        let mut merged = match serde_json::to_value(&defaults) {
+            Ok(serde_json::Value::Object(map)) => map,
+            _ => return defaults,
+        };
+
+        for (key, value) in entries {
+            if !merged.contains_key(&key) {
+                continue;
+            }
+            let mut candidate = merged.clone();
+            candidate.insert(key.clone(), value);
+            if serde_json::from_value::<Self>(serde_json::Value::Object(candidate.clone())).is_ok()
+            {
+                merged = candidate;
+            } else {
+                warn!("Ignoring invalid stored value for '{}'", key);
+            }
+        }
+
+        let mut config: Self =
+            serde_json::from_value(serde_json::Value::Object(merged)).unwrap_or(defaults.clone());
+
+        // Formats are checked once every key is in, since `time_format` is
+        // only meaningful together with `format_dialect`
+        if let Err(e) = config.time_items() {
+            warn!("Ignoring stored time format: {}", e);
+            config.time_format = defaults.time_format;
+            config.format_dialect = defaults.format_dialect;
+        }
+        config
+            .output_formats
+            .retain(|format| output::validate(&format.pattern).is_ok());
+        for rules in [&mut config.ignore_rules, &mut config.allow_rules] {
+            rules.retain(|rule| rules::compile(&rule.pattern).is_ok());
+        }
+        config
+            .custom_detectors
+            .retain(|detector| detector.compile().is_ok());
+        config
+    }
+
+    /// The year range and recent window that decoded instants must fall in
+    pub fn range(&self) -> range::Range {
+        range::Range {
+            min_year: self.min_year,
+            max_year: self.max_year,
+            window: self.recent_window,
+        }
+    }
+
+    /// Resolve the configured time zone, falling back to UTC if it is not recognized
+    pub fn zone(&self) -> Zone {
+        Zone::parse(&self.timezone).unwrap_or_else(|| {
+            warn!("Unknown time zone '{}', using UTC", self.timezone);
+            Zone::Utc
+        })
+    }
+
+    /// Flatten the config into key/value entries for the settings store
+    pub fn to_entries(&self) -> Vec<(String, serde_json::Value)> {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(map)) => map.into_iter().collect(),
+            _ => Vec::new(),
         }
     }
 }
 
+/// What kind of value a HUD payload describes
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadKind {
+    #[default]
+    Timestamp,
+    Duration,
+    Cron,
+}
+
 /// Payload for the show_hud event
 #[derive(Debug, Clone, Serialize)]
 pub struct HudPayload {
+    pub kind: PayloadKind,
     pub formatted_time: String,
     pub raw_value: String,
     pub timestamp_seconds: i64,
+    pub timestamp_milliseconds: i64,
     pub is_milliseconds: bool,
+    /// Localized description relative to now, e.g. "3 hours ago" (timestamps only)
+    pub relative_time: Option<String>,
+    /// Weekday, ISO week, day-of-year and quarter in the configured zone (timestamps only)
+    pub calendar: Option<CalendarInfo>,
+    /// The date in the configured secondary calendar, if any
+    pub alt_calendar: Option<String>,
+    /// Set when `kind` is `Duration`
+    pub duration: Option<DurationInfo>,
+    /// The time in the offset it was recorded in, for git raw dates
+    pub author_time: Option<AuthorTime>,
+    /// Label of the custom detector that decoded the copy
+    pub detector: Option<String>,
+    /// Set when `kind` is `Cron`
+    pub cron: Option<CronInfo>,
+    /// The instant as code literals and chat markup, empty unless `kind` is `Timestamp`
+    pub code: Vec<CodeSnippet>,
+    /// The instant in every configured output format, empty unless `kind` is `Timestamp`
+    pub variants: Vec<FormattedVariant>,
+}
+
+/// Compile the configured time format, falling back to the default one.
+/// Saved configs are validated, so this only guards hand-edited stores.
+fn compile_time_format(config: &TimestampConfig) -> Vec<Item<'static>> {
+    config.time_items().unwrap_or_else(|e| {
+        warn!("Invalid time format '{}': {}", config.time_format, e);
+        TimestampConfig::default().time_items().unwrap_or_default()
+    })
+}
+
+/// Compile the custom detectors, skipping any that fail validation
+fn compile_detectors(config: &TimestampConfig) -> Vec<detector::CompiledDetector> {
+    config
+        .custom_detectors
+        .iter()
+        .filter_map(|detector| match detector.compile() {
+            Ok(compiled) => Some(compiled),
+            Err(e) => {
+                warn!("Skipping custom detector '{}': {}", detector.label, e);
+                None
+            }
+        })
+        .collect()
 }
 
 /// TimeParser handles validation and parsing of timestamp strings
 pub struct TimeParser {
     config: TimestampConfig,
+    zone: Zone,
+    locale: String,
+    date_order: DateOrder,
+    language: Language,
+    /// `time_format` compiled once per config instead of on every copy
+    time_items: Vec<Item<'static>>,
+    names: FormatLocale,
+    /// `output_formats` compiled once per config
+    output_formats: Vec<output::CompiledFormat>,
+    /// `custom_detectors` compiled once per config
+    detectors: Vec<detector::CompiledDetector>,
 }
 
 impl TimeParser {
     pub fn new(config: TimestampConfig) -> Self {
-        Self { config }
+        Self::with_locale(config, &system_locale())
+    }
+
+    /// Create a parser for an explicit locale instead of the system one
+    pub fn with_locale(config: TimestampConfig, locale: &str) -> Self {
+        Self {
+            zone: config.zone(),
+            time_items: compile_time_format(&config),
+            names: config.names_locale(locale),
+            output_formats: output::compile_all(&config.output_formats),
+            detectors: compile_detectors(&config),
+            date_order: config.date_order.resolve(locale),
+            locale: locale.to_string(),
+            language: Language::from_locale(locale),
+            config,
+        }
     }
 
     pub fn update_config(&mut self, config: TimestampConfig) {
+        self.zone = config.zone();
+        self.time_items = compile_time_format(&config);
+        self.names = config.names_locale(&self.locale);
+        self.output_formats = output::compile_all(&config.output_formats);
+        self.detectors = compile_detectors(&config);
+        self.date_order = config.date_order.resolve(&self.locale);
         self.config = config;
     }
 
@@ -77,18 +448,106 @@ impl TimeParser {
         self.config.hud_position
     }
 
+    /// Minimum time between two HUDs for the same re-copied text
+    pub fn reshow_window(&self) -> Duration {
+        Duration::from_millis(self.config.reshow_window_ms)
+    }
+
+    /// Whether decoded timestamps should replace the clipboard text
+    pub fn auto_replace(&self) -> bool {
+        self.config.auto_replace
+    }
+
+    /// How many decoded copies the history keeps
+    pub fn history_limit(&self) -> usize {
+        self.config.history_limit
+    }
+
+    /// The zone decoded instants are shown in
+    pub fn zone(&self) -> Zone {
+        self.zone
+    }
+
     /// Parse a clipboard string and return HudPayload if valid
     pub fn parse(&self, input: &str) -> Option<HudPayload> {
+        self.parse_detailed(input).ok()
+    }
+
+    /// Parse a clipboard string, saying why nothing was shown if it fails.
+    /// The first specific reason from the epoch detectors wins over `NotRecognized`.
+    /// Custom detectors run first, so a user's digits-only format beats the built-in epoch.
+    pub fn parse_detailed(&self, input: &str) -> Result<HudPayload, Rejection> {
         // Step 1: Trim whitespace
         let trimmed = input.trim();
+        if trimmed.is_empty() {
+            return Err(Rejection::Empty);
+        }
+
+        let mut rejection = Rejection::NotRecognized;
+        for detect in [
+            Self::parse_custom,
+            Self::parse_timestamp,
+            Self::parse_git_date,
+        ] {
+            match detect(self, trimmed) {
+                Ok(payload) => return Ok(payload),
+                Err(Rejection::NotRecognized) => {}
+                Err(specific) => {
+                    if rejection == Rejection::NotRecognized {
+                        rejection = specific;
+                    }
+                }
+            }
+        }
+
+        self.parse_expressions(trimmed).map_err(|specific| {
+            if rejection == Rejection::NotRecognized {
+                specific
+            } else {
+                rejection
+            }
+        })
+    }
+
+    /// Detectors past the epoch-based ones, each behind its config flag.
+    /// Durations come late so that they pick up raw integers rejected as timestamps.
+    fn parse_expressions(&self, trimmed: &str) -> Result<HudPayload, Rejection> {
+        let mut rejection = Rejection::NotRecognized;
+        for (enabled, detect) in [
+            (
+                self.config.date_literals,
+                Self::parse_date_literal as fn(&Self, &str) -> Result<HudPayload, Rejection>,
+            ),
+            (self.config.date_math, Self::evaluate),
+            (self.config.cron_mode, Self::parse_cron),
+            (self.config.duration_mode, Self::parse_duration),
+            (self.config.natural_language, Self::parse_natural),
+        ] {
+            if !enabled {
+                continue;
+            }
+            match detect(self, trimmed) {
+                Ok(payload) => return Ok(payload),
+                Err(Rejection::NotRecognized) => {}
+                Err(specific) => {
+                    if rejection == Rejection::NotRecognized {
+                        rejection = specific;
+                    }
+                }
+            }
+        }
+        Err(rejection)
+    }
 
+    /// Parse an all-digit epoch in seconds or milliseconds
+    fn parse_timestamp(&self, trimmed: &str) -> Result<HudPayload, Rejection> {
         // Step 2: Check if string is non-empty and all digits
         if trimmed.is_empty() || !trimmed.chars().all(|c| c.is_ascii_digit()) {
-            return None;
+            return Err(Rejection::NotRecognized);
         }
 
         // Step 3: Parse as number
-        let value: i64 = trimmed.parse().ok()?;
+        let value: i64 = trimmed.parse().map_err(|_| Rejection::Overflow)?;
 
         // Step 4: Determine if seconds or milliseconds based on length
         let (timestamp_seconds, is_milliseconds) = if trimmed.len() <= 10 {
@@ -99,26 +558,270 @@ impl TimeParser {
             (value / 1000, true)
         };
 
-        // Step 5: Convert to DateTime and check year range
-        let datetime = Utc.timestamp_opt(timestamp_seconds, 0).single()?;
-        let year = datetime.year();
+        // Step 5: Convert to DateTime in the configured zone and check the range
+        let datetime = self.zone.at(timestamp_seconds).ok_or(Rejection::Overflow)?;
+        if let Err(rejection) = self.check_range(&datetime) {
+            debug!("Rejected {}: {}", trimmed, rejection);
+            return Err(Rejection::OutOfRange(rejection));
+        }
 
-        if year < self.config.min_year || year > self.config.max_year {
-            debug!(
-                "Year {} out of range [{}, {}]",
-                year, self.config.min_year, self.config.max_year
+        // Step 6: Format the time
+        let timestamp_milliseconds = if is_milliseconds {
+            value
+        } else {
+            value.checked_mul(1000).ok_or(Rejection::Overflow)?
+        };
+        Ok(self.instant_payload(trimmed, &datetime, timestamp_milliseconds, is_milliseconds))
+    }
+
+    /// Check a decoded instant against the year range and the recent window
+    fn check_range(&self, datetime: &DateTime<FixedOffset>) -> Result<(), RangeRejection> {
+        self.config.range().check(
+            datetime.timestamp(),
+            datetime.year(),
+            Utc::now().timestamp(),
+        )
+    }
+
+    /// Run the user's custom detectors in order; the first in range wins
+    fn parse_custom(&self, trimmed: &str) -> Result<HudPayload, Rejection> {
+        let mut rejection = Rejection::NotRecognized;
+        for detector in &self.detectors {
+            let Some(timestamp_milliseconds) = detector.detect(trimmed) else {
+                continue;
+            };
+            let Some(utc) = DateTime::from_timestamp_millis(timestamp_milliseconds) else {
+                rejection = Rejection::Overflow;
+                continue;
+            };
+            let datetime = self.zone.from_utc(&utc);
+            if let Err(range) = self.check_range(&datetime) {
+                debug!("Rejected {} from '{}': {}", trimmed, detector.label, range);
+                rejection = Rejection::OutOfRange(range);
+                continue;
+            }
+            let mut payload = self.instant_payload(
+                trimmed,
+                &datetime,
+                timestamp_milliseconds,
+                detector.is_milliseconds(),
             );
-            return None;
+            payload.detector = Some(detector.label.clone());
+            return Ok(payload);
         }
+        Err(rejection)
+    }
 
-        // Step 6: Format the time
-        let formatted_time = datetime.format(&self.config.time_format).to_string();
+    /// Parse a git raw date `1704067200 +0800` or an `author ... <email> <epoch> <offset>` line
+    fn parse_git_date(&self, trimmed: &str) -> Result<HudPayload, Rejection> {
+        let git_date = gitdate::parse(trimmed).ok_or(Rejection::NotRecognized)?;
+        let datetime = self.zone.at(git_date.seconds).ok_or(Rejection::Overflow)?;
+        if let Err(rejection) = self.check_range(&datetime) {
+            debug!("Rejected git date {}: {}", trimmed, rejection);
+            return Err(Rejection::OutOfRange(rejection));
+        }
+
+        let authored = datetime.with_timezone(&git_date.offset);
+        let timestamp_milliseconds = git_date
+            .seconds
+            .checked_mul(1000)
+            .ok_or(Rejection::Overflow)?;
+        let mut payload = self.instant_payload(trimmed, &datetime, timestamp_milliseconds, false);
+        payload.author_time = Some(AuthorTime {
+            formatted_time: self.format_time(&authored),
+            offset: git_date.offset.to_string(),
+            identity: git_date.identity,
+        });
+        Ok(payload)
+    }
+
+    /// Parse a locale date literal such as `2024年1月1日 08:00` or `01/02/2024`
+    fn parse_date_literal(&self, trimmed: &str) -> Result<HudPayload, Rejection> {
+        let datetime =
+            literal::parse(trimmed, self.date_order, self.zone).ok_or(Rejection::NotRecognized)?;
+        self.checked_instant(trimmed, &datetime)
+    }
+
+    /// Resolve a natural-language date expression against the current time
+    pub fn parse_natural(&self, input: &str) -> Result<HudPayload, Rejection> {
+        let trimmed = input.trim();
+        let datetime =
+            natural::parse(trimmed, Utc::now(), self.zone).ok_or(Rejection::NotRecognized)?;
+        self.checked_instant(trimmed, &datetime)
+    }
+
+    /// Evaluate a date expression such as `2024-01-01T00:00Z + P1M` or the
+    /// difference of two instants, which yields a duration
+    pub fn evaluate(&self, input: &str) -> Result<HudPayload, Rejection> {
+        let trimmed = input.trim();
+        match arith::evaluate(trimmed, Utc::now(), self.zone, self.date_order)
+            .ok_or(Rejection::NotRecognized)?
+        {
+            arith::Outcome::Instant(datetime) => self.checked_instant(trimmed, &datetime),
+            arith::Outcome::Elapsed(millis) => {
+                Ok(self.duration_payload(trimmed, duration::from_millis(millis, self.language)))
+            }
+        }
+    }
+
+    /// Build the payload for an instant read from text, if it is in range
+    fn checked_instant(
+        &self,
+        trimmed: &str,
+        datetime: &DateTime<FixedOffset>,
+    ) -> Result<HudPayload, Rejection> {
+        if let Err(rejection) = self.check_range(datetime) {
+            debug!("Rejected {}: {}", trimmed, rejection);
+            return Err(Rejection::OutOfRange(rejection));
+        }
+        let timestamp_milliseconds = datetime.timestamp_millis();
+        Ok(self.instant_payload(trimmed, datetime, timestamp_milliseconds, false))
+    }
+
+    /// Entries of the tray's "Copy current time" submenu: the time format,
+    /// then every output format in order
+    pub fn now_formats(&self) -> Vec<NowFormat> {
+        std::iter::once(NowFormat::Configured)
+            .chain(
+                self.output_formats
+                    .iter()
+                    .map(|format| NowFormat::Output(format.name.clone())),
+            )
+            .collect()
+    }
+
+    /// The current time rendered for a "Copy current time" entry, or `None`
+    /// if the output format is gone
+    pub fn format_now(&self, format: &NowFormat) -> Option<String> {
+        let now = self.zone.from_utc(&Utc::now());
+        match format {
+            NowFormat::Configured => Some(self.format_time(&now)),
+            NowFormat::Output(name) => self
+                .output_formats
+                .iter()
+                .find(|format| &format.name == name)
+                .map(|format| format.render(&now, self.names)),
+        }
+    }
+
+    /// Render the current time with `pattern` for the settings preview
+    pub fn preview_format(
+        &self,
+        pattern: &str,
+        dialect: FormatDialect,
+    ) -> Result<String, FormatError> {
+        let items = dialect::compile(pattern, dialect)?;
+        let now = self.zone.from_utc(&Utc::now());
+        Ok(self.names.format(&now, &items))
+    }
+
+    /// Render an instant with the configured time format and localized names
+    fn format_time(&self, datetime: &DateTime<FixedOffset>) -> String {
+        self.names.format(datetime, &self.time_items)
+    }
+
+    /// Render an epoch in milliseconds with the output format called `name`
+    pub fn render_variant(&self, name: &str, timestamp_milliseconds: i64) -> Option<String> {
+        let format = self
+            .output_formats
+            .iter()
+            .find(|format| format.name == name)?;
+        let utc = DateTime::from_timestamp_millis(timestamp_milliseconds)?;
+        Some(format.render(&self.zone.from_utc(&utc), self.names))
+    }
+
+    /// Build the payload for a decoded instant
+    fn instant_payload(
+        &self,
+        raw_value: &str,
+        datetime: &DateTime<FixedOffset>,
+        timestamp_milliseconds: i64,
+        is_milliseconds: bool,
+    ) -> HudPayload {
+        let timestamp_seconds = datetime.timestamp();
+        let formatted_time = self.format_time(datetime);
+        let relative_time =
+            relative::describe(timestamp_seconds, Utc::now().timestamp(), self.language);
 
-        Some(HudPayload {
+        HudPayload {
+            kind: PayloadKind::Timestamp,
             formatted_time,
-            raw_value: trimmed.to_string(),
+            raw_value: raw_value.to_string(),
             timestamp_seconds,
+            timestamp_milliseconds,
             is_milliseconds,
+            relative_time: Some(relative_time),
+            calendar: Some(CalendarInfo::new(datetime)),
+            alt_calendar: alt_calendar::render(
+                self.config.calendar_system,
+                datetime,
+                self.language,
+            ),
+            duration: None,
+            author_time: None,
+            detector: None,
+            cron: None,
+            code: snippet::render_all(timestamp_milliseconds),
+            variants: output::render_all(&self.output_formats, datetime, self.names),
+        }
+    }
+
+    /// Parse an ISO 8601 or Go duration, or a raw count of seconds
+    fn parse_duration(&self, trimmed: &str) -> Result<HudPayload, Rejection> {
+        let info =
+            duration::parse_duration(trimmed, self.language).ok_or(Rejection::NotRecognized)?;
+        Ok(self.duration_payload(trimmed, info))
+    }
+
+    /// Build the payload for a decoded duration
+    fn duration_payload(&self, trimmed: &str, info: DurationInfo) -> HudPayload {
+        HudPayload {
+            kind: PayloadKind::Duration,
+            formatted_time: info.human.clone(),
+            raw_value: trimmed.to_string(),
+            timestamp_seconds: info.total_seconds,
+            timestamp_milliseconds: info.total_milliseconds,
+            is_milliseconds: false,
+            relative_time: None,
+            calendar: None,
+            alt_calendar: None,
+            duration: Some(info),
+            author_time: None,
+            detector: None,
+            cron: None,
+            code: Vec::new(),
+            variants: Vec::new(),
+        }
+    }
+
+    /// Explain a cron expression and list its next fire times in the configured zone
+    fn parse_cron(&self, trimmed: &str) -> Result<HudPayload, Rejection> {
+        let schedule = cron::CronSchedule::parse(trimmed).ok_or(Rejection::NotRecognized)?;
+        let now = Utc::now();
+        let runs = schedule.upcoming(now, self.zone, self.config.cron_runs.clamp(1, 20));
+        let next = runs.first();
+        let info = CronInfo {
+            description: schedule.describe(self.language),
+            next_runs: runs.iter().map(|run| self.format_time(run)).collect(),
+        };
+
+        Ok(HudPayload {
+            kind: PayloadKind::Cron,
+            formatted_time: info.description.clone(),
+            raw_value: trimmed.to_string(),
+            timestamp_seconds: next.map_or(0, |run| run.timestamp()),
+            timestamp_milliseconds: next.map_or(0, |run| run.timestamp_millis()),
+            is_milliseconds: false,
+            relative_time: next
+                .map(|run| relative::describe(run.timestamp(), now.timestamp(), self.language)),
+            calendar: None,
+            alt_calendar: None,
+            duration: None,
+            author_time: None,
+            detector: None,
+            cron: Some(info),
+            code: Vec::new(),
+            variants: Vec::new(),
         })
     }
 }
@@ -130,6 +833,14 @@ const CLIPBOARD_POLL_INTERVAL_MS: u64 = 350;
 pub struct ClipboardMonitor {
     parser: Arc<Mutex<TimeParser>>,
     last_content: Arc<Mutex<String>>,
+    /// Recent writes made by the app, which must not show the HUD
+    own_writes: Arc<Mutex<suppress::WriteTracker>>,
+    /// The last auto-replacement as (original, replacement), for restoring
+    replacement: Arc<Mutex<Option<(String, String)>>>,
+    /// Ignore and allow rules checked before parsing
+    rules: Arc<Mutex<rules::RuleSet>>,
+    /// Decoded copies, loaded from the store when monitoring starts
+    history: Arc<Mutex<history::History>>,
     running: Arc<Mutex<bool>>,
 }
 
@@ -140,19 +851,110 @@ impl ClipboardMonitor {
             .and_then(|mut c| c.get_text())
             .unwrap_or_default();
 
+        let rules = rules::RuleSet::new(&config.ignore_rules, &config.allow_rules, None);
+
         Self {
+            rules: Arc::new(Mutex::new(rules)),
             parser: Arc::new(Mutex::new(TimeParser::new(config))),
             last_content: Arc::new(Mutex::new(initial_content)),
+            own_writes: Arc::new(Mutex::new(suppress::WriteTracker::default())),
+            replacement: Arc::new(Mutex::new(None)),
+            history: Arc::new(Mutex::new(history::History::default())),
             running: Arc::new(Mutex::new(true)),
         }
     }
 
     pub fn update_config(&self, config: TimestampConfig) {
+        if let Ok(mut rules) = self.rules.lock() {
+            *rules = rules::RuleSet::new(&config.ignore_rules, &config.allow_rules, Some(&rules));
+        }
+        if let Ok(mut history) = self.history.lock() {
+            history.trim(config.history_limit);
+        }
         if let Ok(mut parser) = self.parser.lock() {
             parser.update_config(config);
         }
     }
 
+    /// Run `f` against the monitor's parser, e.g. to serve commands with the current config
+    pub fn with_parser<R>(&self, f: impl FnOnce(&TimeParser) -> R) -> Option<R> {
+        self.parser.lock().ok().map(|parser| f(&parser))
+    }
+
+    /// Write text to the clipboard on behalf of the app.
+    ///
+    /// Every in-app write must go through here: the write is recorded by
+    /// content hash so the poller skips it instead of showing the HUD.
+    pub fn write_text(&self, text: &str) -> Result<(), String> {
+        let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+        write_own(&self.own_writes, &mut clipboard, text)
+    }
+
+    /// Put back the text that auto-replace overwrote.
+    ///
+    /// Returns `false` when there is nothing to restore, including when the
+    /// clipboard has changed since the replacement.
+    pub fn restore_original(&self) -> Result<bool, String> {
+        let Some((original, replaced)) = self.replacement.lock().map_err(|e| e.to_string())?.take()
+        else {
+            return Ok(false);
+        };
+
+        let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+        if clipboard.get_text().ok().as_deref() != Some(replaced.as_str()) {
+            return Ok(false);
+        }
+        write_own(&self.own_writes, &mut clipboard, &original)?;
+        Ok(true)
+    }
+
+    /// How often each ignore and allow rule has matched since startup
+    pub fn rule_stats(&self) -> RuleStats {
+        self.rules
+            .lock()
+            .map(|rules| rules.stats())
+            .unwrap_or_else(|_| rules::RuleSet::default().stats())
+    }
+
+    /// The newest `count` history entries, newest first
+    pub fn recent_history(&self, count: usize) -> Vec<HistoryEntry> {
+        self.history
+            .lock()
+            .map(|history| history.entries().take(count).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn history_entry(&self, id: u64) -> Option<HistoryEntry> {
+        self.history.lock().ok()?.get(id).cloned()
+    }
+
+    /// History entries matching `query`, newest first
+    pub fn query_history(&self, query: &HistoryQuery) -> Vec<HistoryEntry> {
+        self.history
+            .lock()
+            .map(|history| history.query(query))
+            .unwrap_or_default()
+    }
+
+    /// Pin or unpin a history entry, then persist the history and refresh the tray
+    pub fn set_history_pinned(
+        &self,
+        app_handle: &AppHandle,
+        id: u64,
+        pinned: bool,
+    ) -> Result<(), String> {
+        let mut history = self.history.lock().map_err(|e| e.to_string())?;
+        if !history.set_pinned(id, pinned) {
+            return Err(format!("No history entry {}", id));
+        }
+        history::save(app_handle, &history)?;
+        drop(history);
+
+        let handle = app_handle.clone();
+        let _ = app_handle.run_on_main_thread(move || tray::refresh_history_menu(&handle));
+        Ok(())
+    }
+
     pub fn is_running(&self) -> bool {
         *self.running.lock().unwrap()
     }
@@ -171,8 +973,17 @@ impl ClipboardMonitor {
     pub fn start(&self, app_handle: AppHandle) {
         let parser = Arc::clone(&self.parser);
         let last_content = Arc::clone(&self.last_content);
+        let own_writes = Arc::clone(&self.own_writes);
+        let replacement = Arc::clone(&self.replacement);
+        let rules = Arc::clone(&self.rules);
+        let history = Arc::clone(&self.history);
         let running = Arc::clone(&self.running);
 
+        let limit = self.with_parser(TimeParser::history_limit).unwrap_or(0);
+        if let Ok(mut history) = history.lock() {
+            *history = history::History::new(history::load(&app_handle), limit);
+        }
+
         thread::spawn(move || {
             let mut clipboard = match Clipboard::new() {
                 Ok(c) => c,
@@ -182,65 +993,180 @@ impl ClipboardMonitor {
                 }
             };
 
+            let mut detector = change_count::CopyDetector::new(change_count::current());
+            let mut paused = change_count::PausedWatch::default();
+
             loop {
                 thread::sleep(Duration::from_millis(CLIPBOARD_POLL_INTERVAL_MS));
 
-                // Check if monitoring is paused
+                // Check if monitoring is paused, reporting copies without reading them
                 if !*running.lock().unwrap() {
+                    if paused.observe(change_count::current()) {
+                        emit_rejection(&app_handle, RejectionSummary::unread(Rejection::Paused));
+                    }
                     continue;
                 }
+                paused.reset();
 
                 // Get current clipboard text
+                let count = change_count::current();
                 let current = match clipboard.get_text() {
                     Ok(text) => text,
                     Err(_) => continue,
                 };
 
-                // Check if content changed
+                // Check if content changed, or identical text was copied again
+                let reshow_window = match parser.lock() {
+                    Ok(parser_guard) => parser_guard.reshow_window(),
+                    Err(_) => continue,
+                };
                 let mut last = last_content.lock().unwrap();
-                if current == *last {
+                let text_changed = current != *last;
+                if !detector.observe(text_changed, count, Instant::now(), reshow_window) {
                     continue;
                 }
                 *last = current.clone();
                 drop(last);
 
+                // Skip text the app just wrote itself
+                if own_writes.lock().unwrap().take(&current, Instant::now()) {
+                    debug!("Ignoring clipboard write made by Timesdump");
+                    continue;
+                }
+
+                // Apply the user's ignore and allow rules before parsing
+                if let Ok(mut rules) = rules.lock() {
+                    match rules.evaluate(&current) {
+                        rules::Verdict::Pass => {}
+                        rules::Verdict::Ignored(index) => {
+                            let rule = rules.ignore_name(index).to_string();
+                            emit_rejection(
+                                &app_handle,
+                                RejectionSummary::new(Rejection::IgnoredByRule { rule }, &current),
+                            );
+                            continue;
+                        }
+                        rules::Verdict::NotAllowed => {
+                            emit_rejection(
+                                &app_handle,
+                                RejectionSummary::new(Rejection::NotAllowed, &current),
+                            );
+                            continue;
+                        }
+                    }
+                }
+
                 // Try to parse as timestamp
+                let mut decoded = None;
                 if let Ok(parser_guard) = parser.lock() {
-                    if let Some(payload) = parser_guard.parse(&current) {
-                        info!("Valid timestamp detected: {}", payload.formatted_time);
-
-                        // Clone payload and get position for the closure
-                        let payload_clone = payload.clone();
-                        let hud_position = parser_guard.get_hud_position();
-                        let app_handle_clone = app_handle.clone();
-
-                        // Position and show the HUD window on the main thread
-                        // macOS requires all UI operations to run on the main thread
-                        let _ = app_handle.run_on_main_thread(move || {
-                            if let Some(hud_window) = app_handle_clone.get_webview_window("hud") {
-                                // Position window at the configured fixed position
-                                #[cfg(target_os = "macos")]
-                                ghost_window::position_hud_macos(&hud_window, hud_position);
-
-                                #[cfg(target_os = "windows")]
-                                ghost_window::position_hud_windows(&hud_window, hud_position);
-
-                                #[cfg(target_os = "linux")]
-                                ghost_window::position_hud_linux(&hud_window, hud_position);
-                            }
+                    match parser_guard.parse_detailed(&current) {
+                        Ok(payload) => {
+                            info!("Valid timestamp detected: {}", payload.formatted_time);
 
-                            // Emit event to frontend
-                            if let Err(e) = app_handle_clone.emit("show_hud", payload_clone) {
-                                error!("Failed to emit show_hud event: {}", e);
+                            if parser_guard.auto_replace() && payload.kind == PayloadKind::Timestamp
+                            {
+                                match write_own(
+                                    &own_writes,
+                                    &mut clipboard,
+                                    &payload.formatted_time,
+                                ) {
+                                    Ok(()) => {
+                                        *replacement.lock().unwrap() =
+                                            Some((current.clone(), payload.formatted_time.clone()));
+                                    }
+                                    Err(e) => error!("Failed to replace clipboard text: {}", e),
+                                }
                             }
-                        });
+
+                            // Clone payload and get position for the closure
+                            let payload_clone = payload.clone();
+                            let hud_position = parser_guard.get_hud_position();
+                            let app_handle_clone = app_handle.clone();
+
+                            // Position and show the HUD window on the main thread
+                            // macOS requires all UI operations to run on the main thread
+                            let _ = app_handle.run_on_main_thread(move || {
+                                if let Some(hud_window) = app_handle_clone.get_webview_window("hud")
+                                {
+                                    // Position window at the configured fixed position
+                                    #[cfg(target_os = "macos")]
+                                    ghost_window::position_hud_macos(&hud_window, hud_position);
+
+                                    #[cfg(target_os = "windows")]
+                                    ghost_window::position_hud_windows(&hud_window, hud_position);
+
+                                    #[cfg(target_os = "linux")]
+                                    ghost_window::position_hud_linux(&hud_window, hud_position);
+                                }
+
+                                // Emit event to frontend
+                                if let Err(e) = app_handle_clone.emit("show_hud", payload_clone) {
+                                    error!("Failed to emit show_hud event: {}", e);
+                                }
+                            });
+
+                            decoded = Some((payload, parser_guard.history_limit()));
+                        }
+                        Err(rejection) => {
+                            emit_rejection(&app_handle, RejectionSummary::new(rejection, &current));
+                        }
                     }
                 }
+
+                // Saving writes the store to disk, so do it after releasing the parser
+                if let Some((payload, limit)) = decoded {
+                    record_history(&app_handle, &history, &payload, limit);
+                }
             }
         });
     }
 }
 
+/// Add a decoded copy to the history, persist it and refresh the tray submenu
+fn record_history(
+    app_handle: &AppHandle,
+    history: &Mutex<history::History>,
+    payload: &HudPayload,
+    limit: usize,
+) {
+    if limit == 0 {
+        return;
+    }
+    let Ok(mut history) = history.lock() else {
+        return;
+    };
+    let entry = history::HistoryEntry::from_payload(payload, Utc::now().timestamp_millis());
+    history.record(entry, limit);
+    if let Err(e) = history::save(app_handle, &history) {
+        error!("Failed to save history: {}", e);
+    }
+    drop(history);
+
+    let handle = app_handle.clone();
+    let _ = app_handle.run_on_main_thread(move || tray::refresh_history_menu(&handle));
+}
+
+/// Tell subscribers such as the debug panel why a copy did not show the HUD
+fn emit_rejection(app_handle: &AppHandle, summary: RejectionSummary) {
+    debug!("Copy rejected: {}", summary.rejection);
+    if let Err(e) = app_handle.emit("clipboard_rejected", summary) {
+        error!("Failed to emit clipboard_rejected event: {}", e);
+    }
+}
+
+/// Record `text` as the app's own write, then put it on the clipboard
+fn write_own(
+    own_writes: &Mutex<suppress::WriteTracker>,
+    clipboard: &mut Clipboard,
+    text: &str,
+) -> Result<(), String> {
+    own_writes
+        .lock()
+        .map_err(|e| e.to_string())?
+        .record(text, Instant::now());
+    clipboard.set_text(text).map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -271,6 +1197,26 @@ mod tests {
         assert!(payload.is_milliseconds);
     }
 
+    #[test]
+    fn test_payload_timestamp_milliseconds() {
+        let parser = TimeParser::new(TimestampConfig::default());
+        let payload = parser.parse("1704067200").unwrap();
+        assert_eq!(payload.timestamp_milliseconds, 1704067200000);
+        let payload = parser.parse("1704067200123").unwrap();
+        assert_eq!(payload.timestamp_milliseconds, 1704067200123);
+    }
+
+    #[test]
+    fn test_payload_relative_time() {
+        let parser = TimeParser::new(TimestampConfig::default());
+        assert!(parser.parse("1704067200").unwrap().relative_time.is_some());
+        assert!(parser
+            .parse("1704067200000")
+            .unwrap()
+            .relative_time
+            .is_some());
+    }
+
     #[test]
     fn test_reject_non_numeric() {
         let config = TimestampConfig::default();
@@ -302,4 +1248,498 @@ mod tests {
         let result = parser.parse("  1704067200  ");
         assert!(result.is_some());
     }
+
+    #[test]
+    fn test_calendar_in_configured_zone() {
+        // 2023-12-31 16:00 UTC is already 2024-01-01 in Shanghai
+        let utc_parser = TimeParser::new(TimestampConfig::default());
+        let calendar = utc_parser.parse("1704038400").unwrap().calendar.unwrap();
+        assert_eq!(calendar.ordinal, 365);
+        assert_eq!(calendar.quarter, 4);
+
+        let config = TimestampConfig {
+            timezone: "Asia/Shanghai".to_string(),
+            ..Default::default()
+        };
+        let payload = TimeParser::new(config).parse("1704038400").unwrap();
+        assert_eq!(payload.formatted_time, "2024-01-01 00:00:00");
+        let calendar = payload.calendar.unwrap();
+        assert_eq!(calendar.ordinal, 1);
+        assert_eq!(calendar.quarter, 1);
+        assert_eq!(calendar.iso_weekday, 1);
+        assert_eq!((calendar.iso_week_year, calendar.iso_week), (2024, 1));
+    }
+
+    #[test]
+    fn test_date_literals() {
+        // Off by default like the other opt-in detectors
+        let parser = TimeParser::with_locale(TimestampConfig::default(), "en-US");
+        assert_eq!(
+            parser.parse_detailed("01/02/2024").err(),
+            Some(Rejection::NotRecognized)
+        );
+
+        let config = TimestampConfig {
+            date_literals: true,
+            ..Default::default()
+        };
+        let parser = TimeParser::with_locale(config.clone(), "en-US");
+        let payload = parser.parse("01/02/2024").unwrap();
+        assert_eq!(payload.timestamp_seconds, 1704153600);
+        assert_eq!(payload.timestamp_milliseconds, 1704153600000);
+        assert_eq!(payload.code.len(), CodeTarget::ALL.len());
+
+        let parser = TimeParser::with_locale(config.clone(), "en-GB");
+        assert_eq!(
+            parser.parse("01/02/2024").unwrap().timestamp_seconds,
+            1706745600
+        );
+
+        let config = TimestampConfig {
+            date_order: DateOrder::Mdy,
+            ..config
+        };
+        let parser = TimeParser::with_locale(config, "en-GB");
+        assert_eq!(
+            parser.parse("01/02/2024").unwrap().timestamp_seconds,
+            1704153600
+        );
+
+        let payload = parser.parse("2024年1月1日 08:00").unwrap();
+        assert_eq!(payload.timestamp_seconds, 1704067200 + 8 * 3600);
+
+        // Literals are held to the year range like epochs
+        assert_eq!(
+            parser.parse_detailed("2077年1月1日").err(),
+            Some(Rejection::OutOfRange(RangeRejection::AfterMaxYear {
+                year: 2077,
+                max_year: 2050,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_git_raw_date() {
+        let parser = TimeParser::new(TimestampConfig::default());
+        let payload = parser.parse("1704067200 +0800").unwrap();
+        assert_eq!(payload.timestamp_seconds, 1704067200);
+        assert_eq!(payload.formatted_time, "2024-01-01 00:00:00");
+        let author_time = payload.author_time.unwrap();
+        assert_eq!(author_time.formatted_time, "2024-01-01 08:00:00");
+        assert_eq!(author_time.offset, "+08:00");
+
+        let payload = parser
+            .parse("author Jane Doe <jane@example.com> 1704067200 -0500")
+            .unwrap();
+        let author_time = payload.author_time.unwrap();
+        assert_eq!(author_time.formatted_time, "2023-12-31 19:00:00");
+        assert_eq!(
+            author_time.identity.as_deref(),
+            Some("Jane Doe <jane@example.com>")
+        );
+
+        assert!(parser.parse("1704067200").unwrap().author_time.is_none());
+    }
+
+    #[test]
+    fn test_cron_mode() {
+        let parser = TimeParser::with_locale(TimestampConfig::default(), "en-US");
+        assert!(parser.parse("*/5 * * * *").is_none());
+
+        let config = TimestampConfig {
+            cron_mode: true,
+            cron_runs: 3,
+            ..Default::default()
+        };
+        let parser = TimeParser::with_locale(config, "en-US");
+        let payload = parser.parse("*/5 * * * *").unwrap();
+        assert_eq!(payload.kind, PayloadKind::Cron);
+        assert_eq!(payload.formatted_time, "Every 5 minutes");
+        assert_eq!(payload.timestamp_seconds % 300, 0);
+        assert_eq!(payload.cron.unwrap().next_runs.len(), 3);
+
+        // Epochs still win over cron detection
+        assert_eq!(
+            parser.parse("1704067200").unwrap().kind,
+            PayloadKind::Timestamp
+        );
+    }
+
+    #[test]
+    fn test_date_math() {
+        let parser = TimeParser::with_locale(TimestampConfig::default(), "en-US");
+        assert!(parser.parse("1704067200 + 3d").is_none());
+
+        let payload = parser.evaluate("1704067200 + 3d").unwrap();
+        assert_eq!(payload.timestamp_seconds, 1704067200 + 3 * 86400);
+        assert_eq!(payload.formatted_time, "2024-01-04 00:00:00");
+
+        let config = TimestampConfig {
+            date_math: true,
+            ..Default::default()
+        };
+        let parser = TimeParser::with_locale(config, "en-US");
+        let payload = parser
+            .parse("2024-01-01T01:30Z - 2024-01-01T00:00Z")
+            .unwrap();
+        assert_eq!(payload.kind, PayloadKind::Duration);
+        assert_eq!(payload.formatted_time, "1 h 30 min");
+        assert_eq!(payload.timestamp_milliseconds, 5_400_000);
+
+        assert!(matches!(
+            parser.parse_detailed("1704067200 + 100y"),
+            Err(Rejection::OutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_natural_language() {
+        let parser = TimeParser::new(TimestampConfig::default());
+        assert!(parser.parse("tomorrow 9am").is_none());
+
+        // The command path works without the clipboard opt-in
+        let payload = parser.parse_natural("tomorrow 9am UTC").unwrap();
+        assert_eq!(payload.kind, PayloadKind::Timestamp);
+        assert_eq!(payload.timestamp_seconds % 86_400, 9 * 3600);
+        assert_eq!(
+            payload.timestamp_milliseconds,
+            payload.timestamp_seconds * 1000
+        );
+
+        let config = TimestampConfig {
+            natural_language: true,
+            ..Default::default()
+        };
+        let parser = TimeParser::new(config);
+        assert!(parser.parse("2 hours ago").is_some());
+        assert!(parser.parse("明天下午3点").is_some());
+        assert!(parser.parse("not a date").is_none());
+    }
+
+    #[test]
+    fn test_duration_mode() {
+        let parser = TimeParser::with_locale(TimestampConfig::default(), "en-US");
+        assert!(parser.parse("PT1H30M").is_none());
+        assert!(parser.parse("86400").is_none());
+
+        let config = TimestampConfig {
+            duration_mode: true,
+            ..Default::default()
+        };
+        let parser = TimeParser::with_locale(config, "en-US");
+
+        let payload = parser.parse("86400").unwrap();
+        assert_eq!(payload.kind, PayloadKind::Duration);
+        assert_eq!(payload.formatted_time, "1 day 0 h 0 min");
+
+        let payload = parser.parse("PT1H30M").unwrap();
+        assert_eq!(payload.duration.unwrap().total_milliseconds, 5_400_000);
+
+        // Valid timestamps still take precedence
+        let payload = parser.parse("1704067200").unwrap();
+        assert_eq!(payload.kind, PayloadKind::Timestamp);
+    }
+
+    #[test]
+    fn test_output_variants() {
+        let config = TimestampConfig {
+            timezone: "+08:00".to_string(),
+            ..Default::default()
+        };
+        let parser = TimeParser::new(config);
+        let payload = parser.parse("1704067200").unwrap();
+        let iso = payload
+            .variants
+            .iter()
+            .find(|variant| variant.name == "ISO 8601")
+            .unwrap();
+        assert_eq!(iso.text, "2024-01-01T08:00:00+08:00");
+        assert_eq!(payload.variants.len(), parser.config.output_formats.len());
+
+        assert_eq!(
+            parser.render_variant("Epoch milliseconds", 1704067200123),
+            Some("1704067200123".to_string())
+        );
+        assert_eq!(parser.render_variant("missing", 1704067200123), None);
+    }
+
+    #[test]
+    fn test_now_formats() {
+        let parser = TimeParser::new(TimestampConfig::default());
+        let formats = parser.now_formats();
+        assert_eq!(formats.len(), parser.config.output_formats.len() + 1);
+        assert_eq!(formats[0], NowFormat::Configured);
+
+        let seconds = parser
+            .format_now(&NowFormat::Output("Epoch seconds".to_string()))
+            .unwrap();
+        let now = Utc::now().timestamp();
+        assert!((seconds.parse::<i64>().unwrap() - now).abs() <= 1);
+        let micros = parser
+            .format_now(&NowFormat::Output("Epoch microseconds".to_string()))
+            .unwrap();
+        let now = Utc::now().timestamp_micros();
+        assert_eq!(micros.len(), 16);
+        assert!((micros.parse::<i64>().unwrap() - now).abs() <= 1_000_000);
+        assert_eq!(parser.format_now(&NowFormat::Configured).unwrap().len(), 19);
+        assert_eq!(
+            parser.format_now(&NowFormat::Output("missing".to_string())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_localized_output_variants() {
+        let parser = TimeParser::with_locale(TimestampConfig::default(), "zh-CN");
+        let payload = parser.parse("1704067200").unwrap();
+        let long = payload
+            .variants
+            .iter()
+            .find(|variant| variant.name == "Long")
+            .unwrap();
+        assert_eq!(long.text, "星期一, 一月 1, 2024 12:00:00 上午");
+        // Machine formats keep English names
+        assert_eq!(
+            parser.render_variant("RFC 2822", 1704067200000),
+            Some("Mon, 01 Jan 2024 00:00:00 +0000".to_string())
+        );
+    }
+
+    #[test]
+    fn test_config_from_entries() {
+        let config = TimestampConfig::from_entries(vec![
+            ("min_year".to_string(), serde_json::json!(2000)),
+            ("hud_position".to_string(), serde_json::json!("nowhere")),
+            ("duration_mode".to_string(), serde_json::json!(true)),
+        ]);
+        assert_eq!(config.min_year, 2000);
+        assert_eq!(config.max_year, 2050);
+        assert_eq!(config.hud_position, HudPosition::TopCenter);
+        assert!(config.duration_mode);
+    }
+
+    #[test]
+    fn test_format_dialect() {
+        let config = TimestampConfig {
+            time_format: "DD/MM/YYYY h:mm A".to_string(),
+            format_dialect: FormatDialect::Moment,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        let payload = TimeParser::with_locale(config, "en-US")
+            .parse("1704097845")
+            .unwrap();
+        assert_eq!(payload.formatted_time, "01/01/2024 8:30 AM");
+
+        // The dialect may be stored before or after the format it applies to
+        let loaded = TimestampConfig::from_entries(vec![
+            ("format_dialect".to_string(), serde_json::json!("java")),
+            ("time_format".to_string(), serde_json::json!("yyyy-MM-dd")),
+        ]);
+        assert_eq!(loaded.format_dialect, FormatDialect::Java);
+        assert_eq!(loaded.time_format, "yyyy-MM-dd");
+    }
+
+    #[test]
+    fn test_localized_time_format() {
+        let config = TimestampConfig {
+            time_format: "%Y年%-m月%-d日 %A %p%-I:%M".to_string(),
+            timezone: "+08:00".to_string(),
+            ..Default::default()
+        };
+        let parser = TimeParser::with_locale(config.clone(), "zh-CN");
+        let payload = parser.parse("1704097845").unwrap();
+        assert_eq!(payload.formatted_time, "2024年1月1日 星期一 下午4:30");
+
+        // An explicit locale overrides the system one
+        let parser = TimeParser::with_locale(
+            TimestampConfig {
+                format_locale: "en-US".to_string(),
+                ..config
+            },
+            "zh-CN",
+        );
+        let payload = parser.parse("1704097845").unwrap();
+        assert_eq!(payload.formatted_time, "2024年1月1日 Monday PM4:30");
+
+        assert_eq!(
+            TimestampConfig::for_locale("en-US").time_format,
+            "%m/%d/%Y %-I:%M:%S %p"
+        );
+    }
+
+    #[test]
+    fn test_recent_window() {
+        let now = Utc::now().timestamp();
+        let parser = TimeParser::new(TimestampConfig {
+            recent_window: Some(RecentWindow {
+                before_seconds: 86_400,
+                after_seconds: 3_600,
+            }),
+            ..Default::default()
+        });
+        assert!(parser.parse(&(now - 3_600).to_string()).is_some());
+        assert!(parser.parse(&((now - 3_600) * 1000).to_string()).is_some());
+        // Within the year range but outside the window
+        assert!(parser.parse(&(now - 2 * 86_400).to_string()).is_none());
+        assert!(parser.parse(&(now + 2 * 3_600).to_string()).is_none());
+    }
+
+    #[test]
+    fn test_parse_detailed_rejections() {
+        let parser = TimeParser::new(TimestampConfig::default());
+        assert_eq!(parser.parse_detailed(" \n").err(), Some(Rejection::Empty));
+        assert_eq!(
+            parser.parse_detailed("hello world").err(),
+            Some(Rejection::NotRecognized)
+        );
+        assert_eq!(
+            parser.parse_detailed("99999999999999999999").err(),
+            Some(Rejection::Overflow)
+        );
+        assert_eq!(
+            parser.parse_detailed("9999999999").err(),
+            Some(Rejection::OutOfRange(RangeRejection::AfterMaxYear {
+                year: 2286,
+                max_year: 2050
+            }))
+        );
+        assert!(parser.parse_detailed("1704067200").is_ok());
+    }
+
+    #[test]
+    fn test_rule_validation() {
+        let config = TimestampConfig {
+            allow_rules: vec![ContentRule {
+                name: "broken".to_string(),
+                pattern: "[0-9".to_string(),
+            }],
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(SettingsError::InvalidRule { field, .. }) if field == "allow_rules[0]"
+        ));
+
+        let loaded = TimestampConfig::from_entries(vec![(
+            "ignore_rules".to_string(),
+            serde_json::json!([
+                { "name": "broken", "pattern": "(" },
+                { "name": "phones", "pattern": "^1[3-9]\\d{9}$" }
+            ]),
+        )]);
+        assert_eq!(loaded.ignore_rules.len(), 1);
+        assert_eq!(loaded.ignore_rules[0].name, "phones");
+    }
+
+    #[test]
+    fn test_custom_detectors() {
+        let config = TimestampConfig {
+            custom_detectors: vec![CustomDetector {
+                label: "Mission time".to_string(),
+                pattern: r"^T\+(\d+)$".to_string(),
+                origin: "2015-01-01".to_string(),
+                unit: TimeUnit::Seconds,
+            }],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        let parser = TimeParser::new(config.clone());
+        let payload = parser.parse("T+283996800").unwrap();
+        assert_eq!(payload.timestamp_seconds, 1704067200);
+        assert_eq!(payload.detector.as_deref(), Some("Mission time"));
+        // Built-in detection is unaffected
+        assert_eq!(parser.parse("1704067200").unwrap().detector, None);
+
+        // A digits-only custom format takes precedence over epoch seconds
+        let elapsed = TimestampConfig {
+            custom_detectors: vec![CustomDetector {
+                label: "Elapsed".to_string(),
+                pattern: r"^(\d{10})$".to_string(),
+                origin: "2024-01-01".to_string(),
+                unit: TimeUnit::Milliseconds,
+            }],
+            ..Default::default()
+        };
+        let payload = TimeParser::new(elapsed).parse("1704067200").unwrap();
+        assert_eq!(payload.detector.as_deref(), Some("Elapsed"));
+        assert_eq!(
+            payload.timestamp_milliseconds,
+            1_704_067_200_000 + 1_704_067_200
+        );
+
+        let broken = TimestampConfig {
+            custom_detectors: vec![CustomDetector {
+                pattern: r"T\+\d+".to_string(),
+                ..config.custom_detectors[0].clone()
+            }],
+            ..Default::default()
+        };
+        assert!(matches!(
+            broken.validate(),
+            Err(SettingsError::InvalidDetector { field, .. }) if field == "custom_detectors[0]"
+        ));
+
+        // Built-in names stay reserved for the history filter
+        let reserved = TimestampConfig {
+            custom_detectors: vec![CustomDetector {
+                label: "Cron".to_string(),
+                ..config.custom_detectors[0].clone()
+            }],
+            ..Default::default()
+        };
+        assert!(matches!(
+            reserved.validate(),
+            Err(SettingsError::InvalidDetector { field, .. }) if field == "custom_detectors[0]"
+        ));
+    }
+
+    #[test]
+    fn test_format_validation() {
+        let mut config = TimestampConfig {
+            time_format: "%Y-%m-%d %Q".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(SettingsError::InvalidFormat {
+                field: "time_format".to_string(),
+                token: "%Q".to_string(),
+                position: 9,
+            })
+        );
+
+        config.time_format = "%H:%M".to_string();
+        config.output_formats[1].pattern = "%K".to_string();
+        assert!(matches!(
+            config.validate(),
+            Err(SettingsError::InvalidFormat { field, .. }) if field == "output_formats[1]"
+        ));
+
+        // A stored pattern that would panic when formatted falls back to the default
+        let loaded = TimestampConfig::from_entries(vec![(
+            "time_format".to_string(),
+            serde_json::json!("%Y %Q"),
+        )]);
+        assert_eq!(loaded.time_format, TimestampConfig::default().time_format);
+
+        let parser = TimeParser::new(TimestampConfig::default());
+        assert!(parser
+            .preview_format("%Y-%m-%d", FormatDialect::Strftime)
+            .is_ok());
+        assert_eq!(
+            parser
+                .preview_format("%Q", FormatDialect::Strftime)
+                .unwrap_err()
+                .token,
+            "%Q"
+        );
+        assert_eq!(
+            parser
+                .preview_format("YYYY-MM-DD Do", FormatDialect::Moment)
+                .unwrap_err()
+                .token,
+            "o"
+        );
+    }
 }
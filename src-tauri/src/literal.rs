@@ -0,0 +1,204 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::natural::{parse_chinese_time, parse_english_time};
+use crate::zone::Zone;
+
+/// Longest date literal worth trying, e.g. `2024年12月31日 下午11点59分`
+const MAX_INPUT_CHARS: usize = 40;
+
+/// How to read ambiguous numeric dates such as `01/02/2024`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DateOrder {
+    /// Follow the system locale: month first for US English, day first elsewhere
+    #[default]
+    Auto,
+    /// Day, month, year (`02/01/2024` is 2 January)
+    Dmy,
+    /// Month, day, year (`01/02/2024` is 2 January)
+    Mdy,
+}
+
+impl DateOrder {
+    /// Resolve `Auto` against a BCP 47 locale such as `en-US` or `de-DE`
+    pub fn resolve(self, locale: &str) -> DateOrder {
+        match self {
+            DateOrder::Auto => {
+                let normalized = locale.replace('_', "-").to_lowercase();
+                let month_first = matches!(
+                    normalized.as_str(),
+                    "en" | "en-us" | "en-ph" | "es-us" | "en-as" | "en-gu" | "en-um"
+                ) || normalized.starts_with("en-us-");
+                if month_first {
+                    DateOrder::Mdy
+                } else {
+                    DateOrder::Dmy
+                }
+            }
+            explicit => explicit,
+        }
+    }
+}
+
+/// Parse a locale date literal such as `2024年1月1日 08:00`, `2024/1/1 上午8:00`,
+/// `01/02/2024` or `31.12.2024 23:59`. The wall time is interpreted in `zone`.
+pub fn parse(input: &str, order: DateOrder, zone: Zone) -> Option<DateTime<FixedOffset>> {
+    let input = input.trim();
+    if input.is_empty() || input.chars().count() > MAX_INPUT_CHARS {
+        return None;
+    }
+
+    let (date, rest) = parse_chinese_date(input).or_else(|| parse_numeric_date(input, order))?;
+    let time = parse_time(rest.trim())?;
+    zone.from_local(&date.and_time(time))
+}
+
+/// `2024年1月1日` followed by anything
+fn parse_chinese_date(input: &str) -> Option<(NaiveDate, &str)> {
+    let (year, rest) = input.split_once('年')?;
+    let (month, rest) = rest.split_once('月')?;
+    let (day, rest) = rest.split_once('日').or_else(|| rest.split_once('号'))?;
+    let date = NaiveDate::from_ymd_opt(
+        parse_number(year.trim())?,
+        parse_number(month.trim())? as u32,
+        parse_number(day.trim())? as u32,
+    )?;
+    Some((date, rest))
+}
+
+/// `Y/M/D`, `D/M/Y` or `M/D/Y` with `/`, `-` or `.` separators, followed by anything
+fn parse_numeric_date(input: &str, order: DateOrder) -> Option<(NaiveDate, &str)> {
+    let date_len = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '/' || c == '-' || c == '.'))
+        .unwrap_or(input.len());
+    let (date_part, rest) = input.split_at(date_len);
+    let date_part = date_part.trim_end_matches('.');
+
+    let separator = date_part.chars().find(|c| !c.is_ascii_digit())?;
+    let parts: Vec<&str> = date_part.split(separator).collect();
+    let [first, second, third] = parts.as_slice() else {
+        return None;
+    };
+    let values = [
+        parse_number(first)?,
+        parse_number(second)?,
+        parse_number(third)?,
+    ];
+
+    let (year, month, day) = if first.len() == 4 {
+        (values[0], values[1], values[2])
+    } else if third.len() == 4 {
+        // A component above 12 can only be the day, whatever the preference
+        let day_first = if values[0] > 12 {
+            true
+        } else if values[1] > 12 {
+            false
+        } else {
+            order != DateOrder::Mdy
+        };
+        if day_first {
+            (values[2], values[1], values[0])
+        } else {
+            (values[2], values[0], values[1])
+        }
+    } else {
+        return None;
+    };
+
+    let date = NaiveDate::from_ymd_opt(year, u32::try_from(month).ok()?, u32::try_from(day).ok()?)?;
+    Some((date, rest))
+}
+
+/// Optional time after the date: `08:00`, `8:00 PM`, `上午8:00`, `下午3点`
+fn parse_time(text: &str) -> Option<NaiveTime> {
+    if text.is_empty() {
+        return Some(NaiveTime::MIN);
+    }
+    let text = text.strip_prefix('T').unwrap_or(text);
+    if !text.is_ascii() {
+        return parse_chinese_time(&text.replace(' ', ""));
+    }
+    parse_english_time(&text.to_lowercase().replace(' ', ""))
+}
+
+fn parse_number(value: &str) -> Option<i32> {
+    if value.is_empty() || value.len() > 4 || !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0)
+            .unwrap()
+            .timestamp()
+    }
+
+    fn parse_ts(input: &str, order: DateOrder) -> Option<i64> {
+        parse(input, order, Zone::Utc).map(|dt| dt.timestamp())
+    }
+
+    #[test]
+    fn test_chinese_literals() {
+        assert_eq!(
+            parse_ts("2024年1月1日 08:00", DateOrder::Auto),
+            Some(utc(2024, 1, 1, 8, 0))
+        );
+        assert_eq!(
+            parse_ts("2024/1/1 上午8:00", DateOrder::Auto),
+            Some(utc(2024, 1, 1, 8, 0))
+        );
+        assert_eq!(
+            parse_ts("2024年1月1日下午3点", DateOrder::Auto),
+            Some(utc(2024, 1, 1, 15, 0))
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_order() {
+        assert_eq!(
+            parse_ts("01/02/2024", DateOrder::Mdy),
+            Some(utc(2024, 1, 2, 0, 0))
+        );
+        assert_eq!(
+            parse_ts("01/02/2024", DateOrder::Dmy),
+            Some(utc(2024, 2, 1, 0, 0))
+        );
+        // 13 can only be a day
+        assert_eq!(
+            parse_ts("13/01/2024", DateOrder::Mdy),
+            Some(utc(2024, 1, 13, 0, 0))
+        );
+        assert_eq!(
+            parse_ts("31.12.2024 23:59", DateOrder::Auto),
+            Some(utc(2024, 12, 31, 23, 59))
+        );
+        assert_eq!(
+            parse_ts("1/2/2024 8:00 PM", DateOrder::Mdy),
+            Some(utc(2024, 1, 2, 20, 0))
+        );
+    }
+
+    #[test]
+    fn test_resolve_auto() {
+        assert_eq!(DateOrder::Auto.resolve("en-US"), DateOrder::Mdy);
+        assert_eq!(DateOrder::Auto.resolve("en-GB"), DateOrder::Dmy);
+        assert_eq!(DateOrder::Auto.resolve("zh-CN"), DateOrder::Dmy);
+        assert_eq!(DateOrder::Dmy.resolve("en-US"), DateOrder::Dmy);
+    }
+
+    #[test]
+    fn test_reject_non_dates() {
+        assert!(parse_ts("1704067200", DateOrder::Auto).is_none());
+        assert!(parse_ts("1.2.3", DateOrder::Auto).is_none());
+        assert!(parse_ts("2024/13/45", DateOrder::Auto).is_none());
+        assert!(parse_ts("2024/1/1 hello", DateOrder::Auto).is_none());
+        assert!(parse_ts("192.168.1.1", DateOrder::Auto).is_none());
+    }
+}
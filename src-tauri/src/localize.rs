@@ -0,0 +1,127 @@
+//! Month and weekday names for formatted output, from chrono's locale data.
+
+use crate::literal::DateOrder;
+use chrono::format::Item;
+use chrono::{DateTime, FixedOffset, Locale};
+
+/// Locale for month, weekday and AM/PM names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatLocale(Locale);
+
+impl Default for FormatLocale {
+    fn default() -> Self {
+        FormatLocale(Locale::POSIX)
+    }
+}
+
+/// Regions that write times on a 12-hour clock
+const TWELVE_HOUR_REGIONS: &[&str] = &[
+    "us", "ca", "au", "nz", "in", "ph", "pk", "eg", "sa", "kr", "tw",
+];
+
+impl FormatLocale {
+    /// Pick the locale for a BCP 47 tag such as `zh-CN` or `de_AT`. A tag
+    /// without a known region falls back to the language's main region,
+    /// and unknown languages keep English.
+    pub fn from_tag(tag: &str) -> Self {
+        let normalized = tag.replace('_', "-").to_lowercase();
+        let mut parts = normalized.split('-');
+        let language = parts.next().unwrap_or_default();
+        let region = parts.find(|part| part.len() == 2);
+
+        let main_region = match language {
+            "en" => "us",
+            "zh" => "cn",
+            "ja" => "jp",
+            "ko" => "kr",
+            _ => language,
+        };
+        let locale = region.into_iter().chain([main_region]).find_map(|region| {
+            Locale::try_from(format!("{}_{}", language, region.to_uppercase()).as_str()).ok()
+        });
+        locale.map(FormatLocale).unwrap_or_default()
+    }
+
+    /// Format `datetime` with compiled items, printing names in this locale
+    pub fn format(self, datetime: &DateTime<FixedOffset>, items: &[Item<'static>]) -> String {
+        datetime
+            .format_localized_with_items(items.iter(), self.0)
+            .to_string()
+    }
+}
+
+/// The `time_format` offered on first run: the locale's usual date order
+/// and a 12- or 24-hour clock depending on the region
+pub fn default_time_format(tag: &str) -> String {
+    let normalized = tag.replace('_', "-").to_lowercase();
+    let mut parts = normalized.split('-');
+    let language = parts.next().unwrap_or_default();
+    let region = parts
+        .find(|part| part.len() == 2)
+        .unwrap_or(match language {
+            "en" => "us",
+            "ko" => "kr",
+            _ => "",
+        });
+    let twelve_hour = TWELVE_HOUR_REGIONS.contains(&region);
+
+    let date = match language {
+        "zh" | "ko" => "%Y-%m-%d",
+        "ja" => "%Y/%m/%d",
+        "de" => "%d.%m.%Y",
+        _ => match DateOrder::Auto.resolve(tag) {
+            DateOrder::Mdy => "%m/%d/%Y",
+            _ => "%d/%m/%Y",
+        },
+    };
+    let time = match (twelve_hour, language) {
+        (false, _) => "%H:%M:%S",
+        (true, "zh") => "%p%-I:%M:%S",
+        (true, "ko") => "%p %-I:%M:%S",
+        (true, _) => "%-I:%M:%S %p",
+    };
+    format!("{} {}", date, time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::format::StrftimeItems;
+    use chrono::TimeZone;
+
+    fn render(locale: &str, pattern: &str) -> String {
+        let items: Vec<Item<'static>> = StrftimeItems::new(pattern).map(Item::to_owned).collect();
+        let datetime = FixedOffset::east_opt(8 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 3, 4, 15, 30, 0)
+            .unwrap();
+        FormatLocale::from_tag(locale).format(&datetime, &items)
+    }
+
+    #[test]
+    fn test_localized_names() {
+        let pattern = "%A %a %B %b";
+        assert_eq!(render("en-US", pattern), "Monday Mon March Mar");
+        assert_eq!(render("zh-CN", pattern), "星期一 一 三月 3月");
+        assert_eq!(render("ja_JP", pattern), "月曜日 月 3月  3月");
+        assert_eq!(render("de-AT", pattern), "Montag Mo März Mär");
+        assert_eq!(render("pt-BR", pattern), "segunda seg março mar");
+        assert_eq!(render("xx", pattern), "Monday Mon March Mar");
+        assert_eq!(render("zh-CN", "%p%-I"), "下午3");
+        assert_eq!(render("ko", "%p %-I"), "오후 3");
+        // Numeric items are untouched
+        assert_eq!(render("fr-FR", "%-d %B %Y"), "4 mars 2024");
+    }
+
+    #[test]
+    fn test_default_time_format() {
+        assert_eq!(default_time_format("en-US"), "%m/%d/%Y %-I:%M:%S %p");
+        assert_eq!(default_time_format("en"), "%m/%d/%Y %-I:%M:%S %p");
+        assert_eq!(default_time_format("en-GB"), "%d/%m/%Y %H:%M:%S");
+        assert_eq!(default_time_format("en-AU"), "%d/%m/%Y %-I:%M:%S %p");
+        assert_eq!(default_time_format("zh-CN"), "%Y-%m-%d %H:%M:%S");
+        assert_eq!(default_time_format("zh-Hant-TW"), "%Y-%m-%d %p%-I:%M:%S");
+        assert_eq!(default_time_format("ko"), "%Y-%m-%d %p %-I:%M:%S");
+        assert_eq!(default_time_format("de-DE"), "%d.%m.%Y %H:%M:%S");
+    }
+}
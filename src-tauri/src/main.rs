@@ -3,113 +3,249 @@
     windows_subsystem = "windows"
 )]
 
-use arboard::Clipboard;
+use chrono::Utc;
 use log::info;
 use std::sync::Arc;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 use timesdump_lib::{
-    setup_ghost_window, setup_tray_menu, ClipboardMonitor, HudPosition, TimestampConfig,
+    refresh_copy_now_menu, setup_ghost_window, setup_tray_menu, system_locale, CalendarEvent,
+    ClipboardMonitor, CodeTarget, EventDraft, ExportFormat, FormatDialect, FormatError,
+    HistoryEntry, HistoryQuery, HudPayload, Rejection, RuleStats, SettingsError, TimeParser,
+    TimestampConfig,
 };
 
 /// Get the system locale
 #[tauri::command]
 fn get_system_locale() -> String {
-    sys_locale::get_locale().unwrap_or_else(|| "en-US".to_string())
+    system_locale()
 }
 
 /// Copy the result to clipboard
 #[tauri::command]
-fn copy_result(text: String) -> Result<(), String> {
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard.set_text(text).map_err(|e| e.to_string())?;
-    Ok(())
+fn copy_result(text: String, state: tauri::State<Arc<ClipboardMonitor>>) -> Result<(), String> {
+    state.write_text(&text)
 }
 
-/// Save settings to store
+/// Copy the decoded instant as a code literal or chat markup, e.g. `python` or `discord`
 #[tauri::command]
-async fn save_settings(
-    app: AppHandle,
-    min_year: i32,
-    max_year: i32,
-    display_duration_ms: u64,
-    time_format: String,
-    hud_position: String,
+fn copy_result_as_code(
+    timestamp_milliseconds: i64,
+    target: String,
+    state: tauri::State<Arc<ClipboardMonitor>>,
+) -> Result<(), String> {
+    let text = CodeTarget::from_id(&target)
+        .ok_or_else(|| format!("Unknown code target '{}'", target))?
+        .render(timestamp_milliseconds)
+        .ok_or_else(|| format!("Timestamp {} is out of range", timestamp_milliseconds))?;
+    state.write_text(&text)
+}
+
+/// Copy the decoded instant in one of the configured output formats
+#[tauri::command]
+fn copy_variant(
+    name: String,
+    timestamp_milliseconds: i64,
+    state: tauri::State<Arc<ClipboardMonitor>>,
 ) -> Result<(), String> {
+    let text = state
+        .with_parser(|parser| parser.render_variant(&name, timestamp_milliseconds))
+        .flatten()
+        .ok_or_else(|| format!("Unknown output format '{}'", name))?;
+    state.write_text(&text)
+}
+
+/// Put back the clipboard text that auto-replace overwrote
+#[tauri::command]
+fn restore_clipboard(state: tauri::State<Arc<ClipboardMonitor>>) -> Result<bool, String> {
+    state.restore_original()
+}
+
+/// Settings store file
+const SETTINGS_STORE: &str = "settings.json";
+
+/// Read the persisted config, using defaults for missing or invalid keys
+fn read_config(app: &AppHandle) -> Result<TimestampConfig, String> {
     use tauri_plugin_store::StoreExt;
 
-    // Parse hud_position string to enum
-    let hud_position_enum: HudPosition =
-        serde_json::from_value(serde_json::json!(hud_position)).unwrap_or_default();
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    let entries = store.entries();
+    if entries.is_empty() {
+        // First run: nothing saved yet, so start from the locale's conventions
+        return Ok(TimestampConfig::for_locale(&system_locale()));
+    }
+    Ok(TimestampConfig::from_entries(entries))
+}
 
-    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+/// Save settings to store
+#[tauri::command]
+async fn save_settings(app: AppHandle, config: TimestampConfig) -> Result<(), SettingsError> {
+    use tauri_plugin_store::StoreExt;
 
-    store.set("min_year", serde_json::json!(min_year));
-    store.set("max_year", serde_json::json!(max_year));
-    store.set(
-        "display_duration_ms",
-        serde_json::json!(display_duration_ms),
-    );
-    store.set("time_format", serde_json::json!(time_format));
-    store.set("hud_position", serde_json::json!(hud_position_enum));
-    store.save().map_err(|e| e.to_string())?;
+    config.validate()?;
 
-    // Update the clipboard monitor with new config
-    let new_config = TimestampConfig {
-        min_year,
-        max_year,
-        display_duration_ms,
-        time_format,
-        hud_position: hud_position_enum,
+    let storage = |e: tauri_plugin_store::Error| SettingsError::Storage {
+        message: e.to_string(),
     };
+    let store = app.store(SETTINGS_STORE).map_err(storage)?;
+
+    for (key, value) in config.to_entries() {
+        store.set(key, value);
+    }
+    store.save().map_err(storage)?;
+
+    // Update the clipboard monitor with new config
     if let Some(monitor) = app.try_state::<Arc<ClipboardMonitor>>() {
-        monitor.update_config(new_config);
+        monitor.update_config(config);
         info!("Updated clipboard monitor config");
     }
 
+    // The "Copy current time" submenu lists the output formats
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || refresh_copy_now_menu(&handle));
+
     Ok(())
 }
 
 /// Load settings from store
 #[tauri::command]
 async fn load_settings(app: AppHandle) -> Result<TimestampConfig, String> {
-    use tauri_plugin_store::StoreExt;
+    read_config(&app)
+}
+
+/// Resolve a natural-language date expression such as "next Friday 15:00"
+#[tauri::command]
+fn parse_natural_date(
+    text: String,
+    state: tauri::State<Arc<ClipboardMonitor>>,
+) -> Result<HudPayload, String> {
+    match state.with_parser(|parser| parser.parse_natural(&text)) {
+        Some(Ok(payload)) => Ok(payload),
+        Some(Err(Rejection::OutOfRange(range))) => Err(format!("'{}': {}", text.trim(), range)),
+        _ => Err(format!("Could not understand '{}' as a date", text.trim())),
+    }
+}
+
+/// Evaluate a date expression such as `now - 90m` or `2024-01-01T00:00Z + P1M`
+#[tauri::command]
+fn evaluate_date_expression(
+    expression: String,
+    state: tauri::State<Arc<ClipboardMonitor>>,
+) -> Result<HudPayload, String> {
+    match state.with_parser(|parser| parser.evaluate(&expression)) {
+        Some(Ok(payload)) => Ok(payload),
+        Some(Err(Rejection::OutOfRange(range))) => {
+            Err(format!("'{}': {}", expression.trim(), range))
+        }
+        _ => Err(format!("Could not evaluate '{}'", expression.trim())),
+    }
+}
+
+/// Render the current time with a strftime pattern so settings can preview it live
+#[tauri::command]
+fn preview_format(
+    pattern: String,
+    dialect: FormatDialect,
+    state: tauri::State<Arc<ClipboardMonitor>>,
+) -> Result<String, FormatError> {
+    state
+        .with_parser(|parser| parser.preview_format(&pattern, dialect))
+        .unwrap_or_else(|| {
+            TimeParser::new(TimestampConfig::default()).preview_format(&pattern, dialect)
+        })
+}
+
+/// Hit counts of the ignore and allow rules since startup
+#[tauri::command]
+fn get_rule_stats(state: tauri::State<Arc<ClipboardMonitor>>) -> RuleStats {
+    state.rule_stats()
+}
+
+/// Decoded copies matching a search, newest first
+#[tauri::command]
+fn query_history(
+    query: HistoryQuery,
+    state: tauri::State<Arc<ClipboardMonitor>>,
+) -> Vec<HistoryEntry> {
+    state.query_history(&query)
+}
 
-    let store = app.store("settings.json").map_err(|e| e.to_string())?;
-
-    let min_year = store
-        .get("min_year")
-        .and_then(|v| v.as_i64())
-        .map(|v| v as i32)
-        .unwrap_or(1990);
-
-    let max_year = store
-        .get("max_year")
-        .and_then(|v| v.as_i64())
-        .map(|v| v as i32)
-        .unwrap_or(2050);
-
-    let display_duration_ms = store
-        .get("display_duration_ms")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(5000);
-
-    let time_format = store
-        .get("time_format")
-        .and_then(|v| v.as_str().map(String::from))
-        .unwrap_or_else(|| "%Y-%m-%d %H:%M:%S".to_string());
-
-    let hud_position = store
-        .get("hud_position")
-        .and_then(|v| serde_json::from_value(v.clone()).ok())
+/// Pin a history entry so trimming keeps it, or unpin it
+#[tauri::command]
+fn pin_history_entry(
+    app: AppHandle,
+    id: u64,
+    pinned: bool,
+    state: tauri::State<Arc<ClipboardMonitor>>,
+) -> Result<(), String> {
+    state.set_history_pinned(&app, id, pinned)
+}
+
+/// Ask the user where to save `contents`, then write it there.
+/// Returns the chosen path, or `None` if the dialog was cancelled.
+fn save_with_dialog(
+    app: &AppHandle,
+    file_name: &str,
+    extension: &str,
+    contents: &str,
+) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let Some(path) = app
+        .dialog()
+        .file()
+        .set_file_name(file_name)
+        .add_filter(extension, &[extension])
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(Some(path.display().to_string()))
+}
+
+/// Export the history entries matching a search to a file the user picks
+#[tauri::command]
+async fn export_history(
+    app: AppHandle,
+    query: HistoryQuery,
+    format: ExportFormat,
+) -> Result<Option<String>, String> {
+    let monitor = app
+        .try_state::<Arc<ClipboardMonitor>>()
+        .ok_or("Clipboard monitor is not running")?;
+    let entries = monitor.query_history(&query);
+    let zone = monitor.with_parser(TimeParser::zone).unwrap_or_default();
+    let contents = format.render(&entries, zone);
+
+    let file_name = format!("timesdump-history.{}", format.extension());
+    save_with_dialog(&app, &file_name, format.extension(), &contents)
+}
+
+/// Save a decoded instant as an iCalendar event in the configured zone
+#[tauri::command]
+async fn export_ics(app: AppHandle, event: CalendarEvent) -> Result<Option<String>, String> {
+    let zone = app
+        .try_state::<Arc<ClipboardMonitor>>()
+        .and_then(|monitor| monitor.with_parser(TimeParser::zone))
         .unwrap_or_default();
+    let contents = event
+        .to_ics(zone, Utc::now())
+        .ok_or_else(|| format!("Timestamp {} is out of range", event.timestamp_milliseconds))?;
+    save_with_dialog(&app, &event.file_name(), "ics", &contents)
+}
 
-    Ok(TimestampConfig {
-        min_year,
-        max_year,
-        display_duration_ms,
-        time_format,
-        hud_position,
-    })
+/// Open the calendar event editor in the settings window. The HUD can't take
+/// keyboard focus, so the title is typed there.
+#[tauri::command]
+async fn edit_calendar_event(app: AppHandle, draft: EventDraft) -> Result<(), String> {
+    let window = app
+        .get_webview_window("settings")
+        .ok_or("Settings window not found")?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())?;
+    app.emit_to("settings", "edit_calendar_event", draft)
+        .map_err(|e| e.to_string())
 }
 
 /// Toggle monitoring pause state
@@ -149,6 +285,7 @@ fn main() {
             Some(vec![]),
         ))
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_dialog::init())
         .setup(|app| {
             let handle = app.handle().clone();
 
@@ -161,42 +298,16 @@ fn main() {
             info!("Setting up Timesdump application");
 
             // Load saved settings or use defaults
-            let config = {
-                use tauri_plugin_store::StoreExt;
-                if let Ok(store) = app.store("settings.json") {
-                    let min_year = store
-                        .get("min_year")
-                        .and_then(|v| v.as_i64())
-                        .map(|v| v as i32)
-                        .unwrap_or(1990);
-                    let max_year = store
-                        .get("max_year")
-                        .and_then(|v| v.as_i64())
-                        .map(|v| v as i32)
-                        .unwrap_or(2050);
-                    let display_duration_ms = store
-                        .get("display_duration_ms")
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(5000);
-                    let time_format = store
-                        .get("time_format")
-                        .and_then(|v| v.as_str().map(String::from))
-                        .unwrap_or_else(|| "%Y-%m-%d %H:%M:%S".to_string());
-                    let hud_position = store
-                        .get("hud_position")
-                        .and_then(|v| serde_json::from_value(v.clone()).ok())
-                        .unwrap_or_default();
-
-                    info!("Loaded saved settings, hud_position: {:?}", hud_position);
-                    TimestampConfig {
-                        min_year,
-                        max_year,
-                        display_duration_ms,
-                        time_format,
-                        hud_position,
-                    }
-                } else {
-                    info!("Using default settings");
+            let config = match read_config(app.handle()) {
+                Ok(config) => {
+                    info!(
+                        "Loaded saved settings, hud_position: {:?}",
+                        config.hud_position
+                    );
+                    config
+                }
+                Err(e) => {
+                    info!("Using default settings: {}", e);
                     TimestampConfig::default()
                 }
             };
@@ -234,11 +345,23 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             get_system_locale,
             copy_result,
+            copy_result_as_code,
+            copy_variant,
+            restore_clipboard,
             hide_hud,
             show_settings,
             toggle_pause,
             save_settings,
             load_settings,
+            parse_natural_date,
+            evaluate_date_expression,
+            preview_format,
+            get_rule_stats,
+            query_history,
+            pin_history_entry,
+            export_history,
+            export_ics,
+            edit_calendar_event,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
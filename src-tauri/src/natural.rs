@@ -0,0 +1,622 @@
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Months, NaiveDate, NaiveTime, Timelike, Utc, Weekday,
+};
+
+use crate::zone::Zone;
+
+/// Longer inputs are prose, not date expressions
+const MAX_INPUT_CHARS: usize = 64;
+
+/// Resolve an English or Simplified Chinese date expression such as
+/// "next Friday 15:00", "tomorrow 9am UTC", "2 hours ago" or "明天下午3点".
+///
+/// Wall-clock times are interpreted in `zone` unless the input ends with an
+/// explicit zone (`UTC`, `+08:00`, `Asia/Shanghai`).
+pub fn parse(input: &str, now: DateTime<Utc>, zone: Zone) -> Option<DateTime<FixedOffset>> {
+    let input = input.trim();
+    if input.is_empty() || input.chars().count() > MAX_INPUT_CHARS {
+        return None;
+    }
+
+    // A trailing explicit zone overrides the configured one
+    let (input, zone) = match input.rsplit_once(char::is_whitespace) {
+        Some((head, last)) => match explicit_zone(last) {
+            Some(explicit) => (head.trim_end(), explicit),
+            None => (input, zone),
+        },
+        None => (input, zone),
+    };
+
+    if input.chars().any(is_cjk) {
+        parse_chinese(input, now, zone)
+    } else {
+        parse_english(input, now, zone)
+    }
+}
+
+/// Zone names accepted as a suffix. Bare words like "z" are not, to avoid false matches.
+fn explicit_zone(token: &str) -> Option<Zone> {
+    let looks_like_zone = token.eq_ignore_ascii_case("utc")
+        || token.eq_ignore_ascii_case("gmt")
+        || token.starts_with('+')
+        || token.starts_with('-')
+        || token.contains('/');
+    if looks_like_zone {
+        Zone::parse(token)
+    } else {
+        None
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    ('\u{4e00}'..='\u{9fff}').contains(&c)
+}
+
+/// Calendar step of a relative offset
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Seconds(i64),
+    Months(i64),
+}
+
+fn unit_step(unit: &str) -> Option<Step> {
+    Some(match unit {
+        "s" | "sec" | "secs" | "second" | "seconds" | "秒" | "秒钟" => Step::Seconds(1),
+        "m" | "min" | "mins" | "minute" | "minutes" | "分" | "分钟" => Step::Seconds(60),
+        "h" | "hr" | "hrs" | "hour" | "hours" | "小时" | "钟头" => Step::Seconds(3600),
+        "d" | "day" | "days" | "天" | "日" => Step::Seconds(86_400),
+        "w" | "week" | "weeks" | "周" | "星期" | "礼拜" => Step::Seconds(7 * 86_400),
+        "month" | "months" | "月" => Step::Months(1),
+        "y" | "year" | "years" | "年" => Step::Months(12),
+        _ => return None,
+    })
+}
+
+/// Apply `amount` units to `now`, keeping calendar months aligned in `zone`
+fn shift(
+    now: DateTime<Utc>,
+    zone: Zone,
+    steps: &[(i64, Step)],
+    forward: bool,
+) -> Option<DateTime<FixedOffset>> {
+    let mut current = zone.from_utc(&now);
+    for &(amount, step) in steps {
+        current = match step {
+            Step::Seconds(unit) => {
+                let delta = Duration::try_seconds(amount.checked_mul(unit)?)?;
+                if forward {
+                    current.checked_add_signed(delta)?
+                } else {
+                    current.checked_sub_signed(delta)?
+                }
+            }
+            Step::Months(unit) => {
+                let months = Months::new(u32::try_from(amount.checked_mul(unit)?).ok()?);
+                if forward {
+                    current.checked_add_months(months)?
+                } else {
+                    current.checked_sub_months(months)?
+                }
+            }
+        };
+    }
+    Some(current)
+}
+
+fn resolve(zone: Zone, date: NaiveDate, time: Option<NaiveTime>) -> Option<DateTime<FixedOffset>> {
+    zone.from_local(&date.and_time(time.unwrap_or(NaiveTime::MIN)))
+}
+
+// ---------------------------------------------------------------------------
+// English
+// ---------------------------------------------------------------------------
+
+fn parse_english(input: &str, now: DateTime<Utc>, zone: Zone) -> Option<DateTime<FixedOffset>> {
+    let lower = input.to_lowercase().replace(',', " ");
+    let tokens: Vec<&str> = lower.split_whitespace().collect();
+
+    if tokens == ["now"] {
+        return Some(zone.from_utc(&now));
+    }
+    if let Some(result) = parse_english_offset(&tokens, now, zone) {
+        return Some(result);
+    }
+
+    let today = zone.from_utc(&now).date_naive();
+    let (date, rest) = parse_english_day(&tokens, today).unwrap_or((today, &tokens[..]));
+    let consumed_day = rest.len() < tokens.len();
+
+    let rest = match rest.first() {
+        Some(&"at") => &rest[1..],
+        _ => rest,
+    };
+    let time = if rest.is_empty() {
+        None
+    } else {
+        Some(parse_english_time(&rest.concat())?)
+    };
+
+    // Require at least a day or a time, so arbitrary words are not accepted
+    if !consumed_day && time.is_none() {
+        return None;
+    }
+    resolve(zone, date, time)
+}
+
+/// "in 2 hours", "2 hours 30 minutes ago", "3 days from now", "an hour later"
+fn parse_english_offset(
+    tokens: &[&str],
+    now: DateTime<Utc>,
+    zone: Zone,
+) -> Option<DateTime<FixedOffset>> {
+    let (body, forward) = match tokens {
+        ["in", body @ ..] => (body, true),
+        [body @ .., "ago"] => (body, false),
+        [body @ .., "later"] => (body, true),
+        [body @ .., "from", "now"] => (body, true),
+        _ => return None,
+    };
+    if body.is_empty() || body.len() % 2 != 0 {
+        return None;
+    }
+
+    let mut steps = Vec::new();
+    for pair in body.chunks(2) {
+        let amount = match pair[0] {
+            "a" | "an" | "one" => 1,
+            number => number.parse::<i64>().ok()?,
+        };
+        steps.push((amount, unit_step(pair[1])?));
+    }
+    shift(now, zone, &steps, forward)
+}
+
+/// Leading day expression and the tokens after it
+fn parse_english_day<'a>(
+    tokens: &'a [&'a str],
+    today: NaiveDate,
+) -> Option<(NaiveDate, &'a [&'a str])> {
+    let tokens = match tokens.first() {
+        Some(&"on") | Some(&"the") => &tokens[1..],
+        _ => tokens,
+    };
+
+    match tokens {
+        ["day", "after", "tomorrow", rest @ ..] => Some((today + Duration::days(2), rest)),
+        ["day", "before", "yesterday", rest @ ..] => Some((today - Duration::days(2), rest)),
+        ["today" | "tonight", rest @ ..] => Some((today, rest)),
+        ["tomorrow", rest @ ..] => Some((today + Duration::days(1), rest)),
+        ["yesterday", rest @ ..] => Some((today - Duration::days(1), rest)),
+        ["next", day, rest @ ..] => Some((next_weekday(today, english_weekday(day)?, false), rest)),
+        ["last", day, rest @ ..] => Some((last_weekday(today, english_weekday(day)?), rest)),
+        ["this" | "coming", day, rest @ ..] => {
+            Some((next_weekday(today, english_weekday(day)?, true), rest))
+        }
+        [day, rest @ ..] => Some((next_weekday(today, english_weekday(day)?, true), rest)),
+        [] => None,
+    }
+}
+
+fn english_weekday(token: &str) -> Option<Weekday> {
+    Some(match token {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// The first `weekday` after `today`, or on `today` itself when `include_today`
+fn next_weekday(today: NaiveDate, weekday: Weekday, include_today: bool) -> NaiveDate {
+    let mut days = (weekday.num_days_from_monday() as i64
+        - today.weekday().num_days_from_monday() as i64)
+        .rem_euclid(7);
+    if days == 0 && !include_today {
+        days = 7;
+    }
+    today + Duration::days(days)
+}
+
+/// The most recent `weekday` strictly before `today`
+fn last_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let mut days = (today.weekday().num_days_from_monday() as i64
+        - weekday.num_days_from_monday() as i64)
+        .rem_euclid(7);
+    if days == 0 {
+        days = 7;
+    }
+    today - Duration::days(days)
+}
+
+/// "15:00", "15:00:30", "9am", "9:30pm", "noon", "midnight"
+pub(crate) fn parse_english_time(token: &str) -> Option<NaiveTime> {
+    match token {
+        "noon" | "midday" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return Some(NaiveTime::MIN),
+        _ => {}
+    }
+
+    let token = token.replace('.', "");
+    let (clock, meridiem) = if let Some(clock) = token.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = token.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (token.as_str(), None)
+    };
+
+    let mut parts = clock.split(':');
+    let hour: u32 = parse_digits(parts.next()?)?;
+    let minute: u32 = parts.next().map(parse_digits).unwrap_or(Some(0))?;
+    let second: u32 = parts.next().map(parse_digits).unwrap_or(Some(0))?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let hour = match meridiem {
+        Some(is_pm) => {
+            if !(1..=12).contains(&hour) {
+                return None;
+            }
+            hour % 12 + if is_pm { 12 } else { 0 }
+        }
+        // A bare number is only a time when written as a clock
+        None if !clock.contains(':') => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, second)
+}
+
+fn parse_digits(value: &str) -> Option<u32> {
+    if value.is_empty() || value.len() > 2 || !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+// ---------------------------------------------------------------------------
+// Simplified Chinese
+// ---------------------------------------------------------------------------
+
+fn parse_chinese(input: &str, now: DateTime<Utc>, zone: Zone) -> Option<DateTime<FixedOffset>> {
+    let text: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if text == "现在" {
+        return Some(zone.from_utc(&now));
+    }
+    if let Some(result) = parse_chinese_offset(&text, now, zone) {
+        return Some(result);
+    }
+
+    let today = zone.from_utc(&now).date_naive();
+    let (date, rest) = parse_chinese_day(&text, today).unwrap_or((today, text.as_str()));
+    let consumed_day = rest.len() < text.len();
+    let rest = rest.strip_prefix('的').unwrap_or(rest);
+
+    let time = if rest.is_empty() {
+        None
+    } else {
+        Some(parse_chinese_time(rest)?)
+    };
+
+    if !consumed_day && time.is_none() {
+        return None;
+    }
+    resolve(zone, date, time)
+}
+
+/// "3小时前", "两天后", "半小时以后", "10分钟之前"
+fn parse_chinese_offset(
+    text: &str,
+    now: DateTime<Utc>,
+    zone: Zone,
+) -> Option<DateTime<FixedOffset>> {
+    let (body, forward) = ["以前", "之前", "前"]
+        .iter()
+        .find_map(|suffix| text.strip_suffix(suffix).map(|body| (body, false)))
+        .or_else(|| {
+            ["以后", "之后", "后"]
+                .iter()
+                .find_map(|suffix| text.strip_suffix(suffix).map(|body| (body, true)))
+        })?;
+
+    // "半小时" is thirty minutes; everything else is a count followed by a unit
+    if body == "半小时" || body == "半个小时" {
+        return shift(now, zone, &[(30, Step::Seconds(60))], forward);
+    }
+
+    let (amount, unit) = split_chinese_number(body)?;
+    let unit = unit.strip_prefix('个').unwrap_or(unit);
+    shift(now, zone, &[(amount, unit_step(unit)?)], forward)
+}
+
+fn parse_chinese_day(text: &str, today: NaiveDate) -> Option<(NaiveDate, &str)> {
+    const RELATIVE_DAYS: [(&str, i64); 11] = [
+        ("大后天", 3),
+        ("大前天", -3),
+        ("今天", 0),
+        ("今日", 0),
+        ("今晚", 0),
+        ("明天", 1),
+        ("明日", 1),
+        ("后天", 2),
+        ("昨天", -1),
+        ("昨日", -1),
+        ("前天", -2),
+    ];
+    for (word, offset) in RELATIVE_DAYS {
+        if let Some(rest) = text.strip_prefix(word) {
+            // "今晚8点" keeps its evening meaning for the time part
+            let rest = if word == "今晚" {
+                &text[text.len() - rest.len() - "晚".len()..]
+            } else {
+                rest
+            };
+            return Some((today + Duration::days(offset), rest));
+        }
+    }
+
+    // Weeks run Monday to Sunday: 下周五 is Friday of next week
+    let (week_offset, rest) = if let Some(rest) = text.strip_prefix('下') {
+        (1, rest)
+    } else if let Some(rest) = text.strip_prefix('上') {
+        (-1, rest)
+    } else if let Some(rest) = text.strip_prefix('这').or_else(|| text.strip_prefix('本')) {
+        (0, rest)
+    } else {
+        (0, text)
+    };
+    let rest = ["星期", "礼拜", "周"]
+        .iter()
+        .find_map(|prefix| rest.strip_prefix(prefix))?;
+    let mut chars = rest.chars();
+    let weekday = match chars.next()? {
+        '一' => Weekday::Mon,
+        '二' => Weekday::Tue,
+        '三' => Weekday::Wed,
+        '四' => Weekday::Thu,
+        '五' => Weekday::Fri,
+        '六' => Weekday::Sat,
+        '日' | '天' => Weekday::Sun,
+        _ => return None,
+    };
+
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let date = monday
+        + Duration::weeks(week_offset)
+        + Duration::days(weekday.num_days_from_monday() as i64);
+    Some((date, chars.as_str()))
+}
+
+/// "下午3点", "上午9点半", "晚上8点30分", "中午12点", "15:00"
+pub(crate) fn parse_chinese_time(text: &str) -> Option<NaiveTime> {
+    const PERIODS: [(&str, bool); 10] = [
+        ("凌晨", false),
+        ("早上", false),
+        ("早晨", false),
+        ("上午", false),
+        ("中午", true),
+        ("下午", true),
+        ("傍晚", true),
+        ("晚上", true),
+        ("夜里", true),
+        ("晚", true),
+    ];
+    let (afternoon, period, rest) = PERIODS
+        .iter()
+        .find_map(|(word, afternoon)| {
+            text.strip_prefix(word)
+                .map(|rest| (*afternoon, Some(*word), rest))
+        })
+        .unwrap_or((false, None, text));
+
+    let (hour, minute, second) = if rest.contains(':') {
+        // "上午8:00" style clock times
+        let time = parse_english_time(rest)?;
+        (time.hour() as i64, time.minute() as i64, time.second())
+    } else {
+        let (hour, rest) = split_chinese_number(rest)?;
+        let rest = rest
+            .strip_prefix('点')
+            .or_else(|| rest.strip_prefix('时'))?;
+        let (minute, rest) = if let Some(rest) = rest.strip_prefix('半') {
+            (30, rest)
+        } else if let Some(rest) = rest.strip_prefix("一刻") {
+            (15, rest)
+        } else if let Some(rest) = rest.strip_prefix("三刻") {
+            (45, rest)
+        } else if rest.is_empty() || rest == "整" {
+            (0, "")
+        } else {
+            let (minute, rest) = split_chinese_number(rest)?;
+            (minute, rest.strip_prefix('分').unwrap_or(rest))
+        };
+        if !rest.is_empty() {
+            return None;
+        }
+        (hour, minute, 0)
+    };
+    if !(0..=24).contains(&hour) {
+        return None;
+    }
+
+    let hour = match period {
+        // 中午1点 is 13:00 but 中午11点 and 中午12点 stay around noon
+        Some("中午") if hour < 11 => hour + 12,
+        Some("中午") => hour,
+        Some("凌晨") if hour == 12 => 0,
+        // 晚上12点 is midnight, and 夜里2点 is in the small hours
+        Some("晚上" | "夜里" | "晚") if hour == 12 => 0,
+        Some("夜里") if hour < 6 => hour,
+        _ if afternoon && hour < 12 => hour + 12,
+        _ => hour,
+    };
+    NaiveTime::from_hms_opt(hour as u32 % 24, u32::try_from(minute).ok()?, second)
+}
+
+/// Split a leading number written in ASCII digits or Chinese numerals (up to 99)
+fn split_chinese_number(text: &str) -> Option<(i64, &str)> {
+    let end = text
+        .char_indices()
+        .find(|(_, c)| !(c.is_ascii_digit() || "零一二两三四五六七八九十".contains(*c)))
+        .map(|(idx, _)| idx)
+        .unwrap_or(text.len());
+    let (number, rest) = text.split_at(end);
+    if number.is_empty() {
+        return None;
+    }
+    if number.chars().all(|c| c.is_ascii_digit()) {
+        return Some((number.parse().ok()?, rest));
+    }
+
+    let digit = |c: char| {
+        "零一二三四五六七八九"
+            .find(c)
+            .map(|idx| (idx / 3) as i64)
+            .or(if c == '两' { Some(2) } else { None })
+    };
+    let chars: Vec<char> = number.chars().collect();
+    let value = match chars.as_slice() {
+        ['十'] => 10,
+        ['十', ones] => 10 + digit(*ones)?,
+        [tens, '十'] => digit(*tens)? * 10,
+        [tens, '十', ones] => digit(*tens)? * 10 + digit(*ones)?,
+        [single] => digit(*single)?,
+        _ => return None,
+    };
+    Some((value, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Wednesday 2024-01-10 12:00:00 UTC
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 10, 12, 0, 0).unwrap()
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0)
+            .unwrap()
+            .timestamp()
+    }
+
+    fn parse_ts(input: &str, zone: Zone) -> Option<i64> {
+        parse(input, now(), zone).map(|dt| dt.timestamp())
+    }
+
+    #[test]
+    fn test_english_days_and_times() {
+        assert_eq!(
+            parse_ts("next Friday 15:00", Zone::Utc),
+            Some(utc(2024, 1, 12, 15, 0))
+        );
+        assert_eq!(
+            parse_ts("tomorrow 9am UTC", Zone::parse("Asia/Shanghai").unwrap()),
+            Some(utc(2024, 1, 11, 9, 0))
+        );
+        assert_eq!(
+            parse_ts("yesterday at 9:30 pm", Zone::Utc),
+            Some(utc(2024, 1, 9, 21, 30))
+        );
+        assert_eq!(parse_ts("last wed", Zone::Utc), Some(utc(2024, 1, 3, 0, 0)));
+        assert_eq!(parse_ts("noon", Zone::Utc), Some(utc(2024, 1, 10, 12, 0)));
+    }
+
+    #[test]
+    fn test_english_offsets() {
+        assert_eq!(
+            parse_ts("2 hours ago", Zone::Utc),
+            Some(utc(2024, 1, 10, 10, 0))
+        );
+        assert_eq!(
+            parse_ts("in 3 days", Zone::Utc),
+            Some(utc(2024, 1, 13, 12, 0))
+        );
+        assert_eq!(
+            parse_ts("1 month 2 days from now", Zone::Utc),
+            Some(utc(2024, 2, 12, 12, 0))
+        );
+        assert_eq!(parse_ts("now", Zone::Utc), Some(now().timestamp()));
+    }
+
+    #[test]
+    fn test_chinese() {
+        let shanghai = Zone::parse("Asia/Shanghai").unwrap();
+        // 2024-01-11 15:00 in Shanghai
+        assert_eq!(
+            parse_ts("明天下午3点", shanghai),
+            Some(utc(2024, 1, 11, 7, 0))
+        );
+        assert_eq!(
+            parse_ts("下周五上午9点半", Zone::Utc),
+            Some(utc(2024, 1, 19, 9, 30))
+        );
+        assert_eq!(
+            parse_ts("中午12点", Zone::Utc),
+            Some(utc(2024, 1, 10, 12, 0))
+        );
+        assert_eq!(
+            parse_ts("晚上八点十五分", Zone::Utc),
+            Some(utc(2024, 1, 10, 20, 15))
+        );
+        assert_eq!(
+            parse_ts("两小时前", Zone::Utc),
+            Some(utc(2024, 1, 10, 10, 0))
+        );
+        assert_eq!(parse_ts("3天后", Zone::Utc), Some(utc(2024, 1, 13, 12, 0)));
+    }
+
+    #[test]
+    fn test_chinese_night_hours() {
+        assert_eq!(
+            parse_ts("晚上12点", Zone::Utc),
+            Some(utc(2024, 1, 10, 0, 0))
+        );
+        assert_eq!(
+            parse_ts("夜里12点", Zone::Utc),
+            Some(utc(2024, 1, 10, 0, 0))
+        );
+        assert_eq!(parse_ts("夜里2点", Zone::Utc), Some(utc(2024, 1, 10, 2, 0)));
+        assert_eq!(
+            parse_ts("夜里11点", Zone::Utc),
+            Some(utc(2024, 1, 10, 23, 0))
+        );
+        assert_eq!(
+            parse_ts("中午12点", Zone::Utc),
+            Some(utc(2024, 1, 10, 12, 0))
+        );
+    }
+
+    #[test]
+    fn test_chinese_clock_times_keep_period() {
+        assert_eq!(
+            parse_ts("明天下午3:00", Zone::Utc),
+            Some(utc(2024, 1, 11, 15, 0))
+        );
+        assert_eq!(
+            parse_ts("晚上8:30", Zone::Utc),
+            Some(utc(2024, 1, 10, 20, 30))
+        );
+        assert_eq!(
+            parse_ts("上午8:00", Zone::Utc),
+            Some(utc(2024, 1, 10, 8, 0))
+        );
+        assert_eq!(parse_ts("15:00", Zone::Utc), Some(utc(2024, 1, 10, 15, 0)));
+    }
+
+    #[test]
+    fn test_reject_prose() {
+        assert!(parse_ts("hello world", Zone::Utc).is_none());
+        assert!(parse_ts("friday night lights", Zone::Utc).is_none());
+        assert!(parse_ts("1704067200", Zone::Utc).is_none());
+        assert!(parse_ts("你好", Zone::Utc).is_none());
+        assert!(parse_ts("", Zone::Utc).is_none());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::dialect::{self, FormatDialect};
+use crate::localize::FormatLocale;
+use chrono::format::{Fixed, Item, Numeric, StrftimeItems};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A named strftime pattern offered as a "copy as" choice
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OutputFormat {
+    pub name: String,
+    pub pattern: String,
+}
+
+impl OutputFormat {
+    fn new(name: &str, pattern: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+        }
+    }
+}
+
+/// One output format rendered for the decoded instant
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FormattedVariant {
+    pub name: String,
+    pub text: String,
+}
+
+/// A strftime specifier chrono does not understand
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FormatError {
+    /// The offending specifier as typed, e.g. `%Q`
+    pub token: String,
+    /// Byte offset of the specifier within the pattern
+    pub position: usize,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unsupported specifier '{}' at position {}",
+            self.token, self.position
+        )
+    }
+}
+
+/// Check every specifier in `pattern`. Formatting an invalid pattern with
+/// `to_string()` panics, so patterns are validated before they are stored.
+pub fn validate(pattern: &str) -> Result<(), FormatError> {
+    let mut rest = 0;
+    while let Some(offset) = pattern[rest..].find('%') {
+        let start = rest + offset;
+        let end = specifier_end(pattern, start);
+        let token = &pattern[start..end];
+        if StrftimeItems::new(token).any(|item| matches!(item, Item::Error)) {
+            return Err(FormatError {
+                token: token.to_string(),
+                position: start,
+            });
+        }
+        rest = end;
+    }
+    Ok(())
+}
+
+/// End of the specifier starting at `start`: padding and precision
+/// modifiers followed by a single conversion character
+fn specifier_end(pattern: &str, start: usize) -> usize {
+    let mut chars = pattern[start + 1..].char_indices();
+    for (index, c) in chars.by_ref() {
+        if !matches!(c, '-' | '_' | '0'..='9' | '.' | ':' | '#') {
+            return start + 1 + index + c.len_utf8();
+        }
+    }
+    pattern.len()
+}
+
+/// The formats offered before the user edits the list
+pub fn default_formats() -> Vec<OutputFormat> {
+    vec![
+        OutputFormat::new("ISO 8601", "%Y-%m-%dT%H:%M:%S%:z"),
+        OutputFormat::new("RFC 2822", "%a, %d %b %Y %H:%M:%S %z"),
+        OutputFormat::new("Long", "%A, %B %-d, %Y %-I:%M:%S %p"),
+        OutputFormat::new("Epoch seconds", "%s"),
+        OutputFormat::new("Epoch milliseconds", "%s%3f"),
+        OutputFormat::new("Epoch microseconds", "%s%6f"),
+    ]
+}
+
+/// An output format with its pattern compiled to chrono items
+#[derive(Debug, Clone)]
+pub struct CompiledFormat {
+    pub name: String,
+    items: Vec<Item<'static>>,
+    /// Prints a numeric UTC offset or an epoch, so it is meant for machines
+    machine: bool,
+}
+
+impl CompiledFormat {
+    fn new(name: &str, items: Vec<Item<'static>>) -> Self {
+        let machine = items.iter().any(|item| {
+            matches!(
+                item,
+                Item::Numeric(Numeric::Timestamp, _)
+                    | Item::Fixed(
+                        Fixed::TimezoneOffset
+                            | Fixed::TimezoneOffsetZ
+                            | Fixed::TimezoneOffsetColon
+                            | Fixed::TimezoneOffsetColonZ
+                            | Fixed::TimezoneOffsetDoubleColon
+                            | Fixed::TimezoneOffsetTripleColon
+                            | Fixed::RFC2822
+                            | Fixed::RFC3339
+                    )
+            )
+        });
+        Self {
+            name: name.to_string(),
+            items,
+            machine,
+        }
+    }
+
+    /// Format `datetime`, printing month and weekday names in `names`.
+    /// Machine formats such as ISO 8601, RFC 2822 and epochs stay English.
+    pub fn render(&self, datetime: &DateTime<FixedOffset>, names: FormatLocale) -> String {
+        if self.machine {
+            datetime.format_with_items(self.items.iter()).to_string()
+        } else {
+            names.format(datetime, &self.items)
+        }
+    }
+}
+
+/// Compile every format in order, skipping ones with an invalid pattern
+pub fn compile_all(formats: &[OutputFormat]) -> Vec<CompiledFormat> {
+    formats
+        .iter()
+        .filter_map(|format| {
+            let items = dialect::compile(&format.pattern, FormatDialect::Strftime).ok()?;
+            Some(CompiledFormat::new(&format.name, items))
+        })
+        .collect()
+}
+
+/// Render every compiled format in order
+pub fn render_all(
+    formats: &[CompiledFormat],
+    datetime: &DateTime<FixedOffset>,
+    names: FormatLocale,
+) -> Vec<FormattedVariant> {
+    formats
+        .iter()
+        .map(|format| FormattedVariant {
+            name: format.name.clone(),
+            text: format.render(datetime, names),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn datetime() -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(8 * 3600)
+            .unwrap()
+            .timestamp_millis_opt(1_704_067_200_250)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_default_formats() {
+        let variants = render_all(
+            &compile_all(&default_formats()),
+            &datetime(),
+            FormatLocale::default(),
+        );
+        let texts: Vec<&str> = variants.iter().map(|v| v.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            [
+                "2024-01-01T08:00:00+08:00",
+                "Mon, 01 Jan 2024 08:00:00 +0800",
+                "Monday, January 1, 2024 8:00:00 AM",
+                "1704067200",
+                "1704067200250",
+                "1704067200250000",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_skipped() {
+        let formats = vec![
+            OutputFormat::new("broken", "%Q"),
+            OutputFormat::new("year", "%Y"),
+        ];
+        let variants = render_all(&compile_all(&formats), &datetime(), FormatLocale::default());
+        assert_eq!(variants.len(), 1);
+        assert_eq!(variants[0].name, "year");
+    }
+
+    #[test]
+    fn test_localized_names() {
+        let long = compile_all(&[OutputFormat::new("Long", "%A, %B %-d, %Y %p")]);
+        assert_eq!(
+            long[0].render(&datetime(), FormatLocale::from_tag("zh-CN")),
+            "星期一, 一月 1, 2024 上午"
+        );
+        // Formats with an offset or epoch keep English names
+        let variants = render_all(
+            &compile_all(&default_formats()),
+            &datetime(),
+            FormatLocale::from_tag("de-DE"),
+        );
+        assert_eq!(variants[1].text, "Mon, 01 Jan 2024 08:00:00 +0800");
+    }
+
+    #[test]
+    fn test_validate() {
+        for pattern in default_formats().iter().map(|f| f.pattern.as_str()) {
+            assert_eq!(validate(pattern), Ok(()));
+        }
+        assert_eq!(validate("100%% at %H:%M"), Ok(()));
+        assert_eq!(validate("%.3f %::z %-d"), Ok(()));
+
+        let error = |token: &str, position| {
+            Err(FormatError {
+                token: token.to_string(),
+                position,
+            })
+        };
+        assert_eq!(validate("%Y-%m-%d %Q"), error("%Q", 9));
+        assert_eq!(validate("%Y %-K"), error("%-K", 3));
+        assert_eq!(validate("%H:%M %"), error("%", 6));
+        assert_eq!(validate("年%Y %é"), error("%é", 6));
+    }
+}
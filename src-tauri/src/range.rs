@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Accept only instants near the current time, e.g. 5 years before to 1 year after now
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecentWindow {
+    pub before_seconds: u64,
+    pub after_seconds: u64,
+}
+
+/// The rule that kept a decoded instant from showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeRejection {
+    BeforeMinYear {
+        year: i32,
+        min_year: i32,
+    },
+    AfterMaxYear {
+        year: i32,
+        max_year: i32,
+    },
+    /// `seconds` before the start of the recent window
+    BeforeWindow {
+        seconds: i64,
+    },
+    /// `seconds` after the end of the recent window
+    AfterWindow {
+        seconds: i64,
+    },
+}
+
+impl RangeRejection {
+    /// Name of the rule, without the values that would reveal the instant
+    pub fn rule(&self) -> &'static str {
+        match self {
+            RangeRejection::BeforeMinYear { .. } => "before_min_year",
+            RangeRejection::AfterMaxYear { .. } => "after_max_year",
+            RangeRejection::BeforeWindow { .. } => "before_window",
+            RangeRejection::AfterWindow { .. } => "after_window",
+        }
+    }
+}
+
+impl fmt::Display for RangeRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeRejection::BeforeMinYear { year, min_year } => {
+                write!(f, "year {} is before min_year {}", year, min_year)
+            }
+            RangeRejection::AfterMaxYear { year, max_year } => {
+                write!(f, "year {} is after max_year {}", year, max_year)
+            }
+            RangeRejection::BeforeWindow { seconds } => {
+                write!(f, "{}s before the recent window", seconds)
+            }
+            RangeRejection::AfterWindow { seconds } => {
+                write!(f, "{}s after the recent window", seconds)
+            }
+        }
+    }
+}
+
+/// Year bounds and the optional recent window, checked together
+#[derive(Debug, Clone, Copy)]
+pub struct Range {
+    pub min_year: i32,
+    pub max_year: i32,
+    pub window: Option<RecentWindow>,
+}
+
+impl Range {
+    /// Check an instant given as epoch seconds and its year in the display zone.
+    /// The year range is checked first, then the window to the second.
+    pub fn check(
+        &self,
+        timestamp_seconds: i64,
+        year: i32,
+        now_seconds: i64,
+    ) -> Result<(), RangeRejection> {
+        if year < self.min_year {
+            return Err(RangeRejection::BeforeMinYear {
+                year,
+                min_year: self.min_year,
+            });
+        }
+        if year > self.max_year {
+            return Err(RangeRejection::AfterMaxYear {
+                year,
+                max_year: self.max_year,
+            });
+        }
+
+        if let Some(window) = self.window {
+            let start = now_seconds.saturating_sub_unsigned(window.before_seconds);
+            let end = now_seconds.saturating_add_unsigned(window.after_seconds);
+            if timestamp_seconds < start {
+                return Err(RangeRejection::BeforeWindow {
+                    seconds: start - timestamp_seconds,
+                });
+            }
+            if timestamp_seconds > end {
+                return Err(RangeRejection::AfterWindow {
+                    seconds: timestamp_seconds - end,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_704_067_200; // 2024-01-01T00:00:00Z
+    const YEAR: u64 = 365 * 86_400;
+
+    fn range(window: Option<RecentWindow>) -> Range {
+        Range {
+            min_year: 1990,
+            max_year: 2050,
+            window,
+        }
+    }
+
+    #[test]
+    fn test_year_range() {
+        assert_eq!(range(None).check(NOW, 2024, NOW), Ok(()));
+        assert_eq!(
+            range(None).check(0, 1970, NOW),
+            Err(RangeRejection::BeforeMinYear {
+                year: 1970,
+                min_year: 1990
+            })
+        );
+        assert_eq!(
+            range(None).check(4_102_444_800, 2100, NOW),
+            Err(RangeRejection::AfterMaxYear {
+                year: 2100,
+                max_year: 2050
+            })
+        );
+    }
+
+    #[test]
+    fn test_window_is_precise_to_the_second() {
+        let window = range(Some(RecentWindow {
+            before_seconds: 5 * YEAR,
+            after_seconds: YEAR,
+        }));
+        let start = NOW - 5 * YEAR as i64;
+        let end = NOW + YEAR as i64;
+
+        assert_eq!(window.check(start, 2019, NOW), Ok(()));
+        assert_eq!(
+            window.check(start - 1, 2019, NOW),
+            Err(RangeRejection::BeforeWindow { seconds: 1 })
+        );
+        assert_eq!(window.check(end, 2024, NOW), Ok(()));
+        assert_eq!(
+            window.check(end + 1, 2024, NOW),
+            Err(RangeRejection::AfterWindow { seconds: 1 })
+        );
+    }
+
+    #[test]
+    fn test_year_range_still_applies_inside_window() {
+        let window = Range {
+            min_year: 2024,
+            max_year: 2050,
+            window: Some(RecentWindow {
+                before_seconds: 5 * YEAR,
+                after_seconds: YEAR,
+            }),
+        };
+        assert_eq!(
+            window.check(NOW - 1, 2023, NOW),
+            Err(RangeRejection::BeforeMinYear {
+                year: 2023,
+                min_year: 2024
+            })
+        );
+    }
+}
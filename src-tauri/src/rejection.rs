@@ -0,0 +1,167 @@
+use crate::range::RangeRejection;
+use serde::Serialize;
+use std::fmt;
+
+/// Why copied text did not show the HUD
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    /// Nothing but whitespace was copied
+    Empty,
+    /// No detector understood the text
+    NotRecognized,
+    /// The number does not fit in an `i64` or outside chrono's supported dates
+    Overflow,
+    /// Decoded, but outside the year range or recent window
+    OutOfRange(RangeRejection),
+    /// Monitoring was paused when the copy happened. Only reported on macOS
+    /// and Windows, whose change counter shows a copy without reading it.
+    Paused,
+    /// Suppressed by the named ignore rule
+    IgnoredByRule { rule: String },
+    /// Allow rules exist and none matched
+    NotAllowed,
+}
+
+impl Rejection {
+    /// Kind of rejection as sent in `clipboard_rejected`, e.g. `out_of_range`
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Rejection::Empty => "empty",
+            Rejection::NotRecognized => "not_recognized",
+            Rejection::Overflow => "overflow",
+            Rejection::OutOfRange(_) => "out_of_range",
+            Rejection::Paused => "paused",
+            Rejection::IgnoredByRule { .. } => "ignored_by_rule",
+            Rejection::NotAllowed => "not_allowed",
+        }
+    }
+
+    /// The range rule or the user's ignore rule behind the rejection, if any
+    pub fn rule(&self) -> Option<String> {
+        match self {
+            Rejection::OutOfRange(range) => Some(range.rule().to_string()),
+            Rejection::IgnoredByRule { rule } => Some(rule.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Empty => write!(f, "empty"),
+            Rejection::NotRecognized => write!(f, "not recognized"),
+            Rejection::Overflow => write!(f, "out of representable range"),
+            Rejection::OutOfRange(range) => write!(f, "{}", range),
+            Rejection::Paused => write!(f, "monitoring paused"),
+            Rejection::IgnoredByRule { rule } => write!(f, "ignored by rule '{}'", rule),
+            Rejection::NotAllowed => write!(f, "no allow rule matched"),
+        }
+    }
+}
+
+/// Coarse shape of copied text, safe to share without the text itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentShape {
+    Digits,
+    SingleLine,
+    MultiLine,
+}
+
+/// Payload of the `clipboard_rejected` event. It never contains the copied
+/// text, nor decoded values such as the year that would let it be rebuilt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RejectionSummary {
+    /// Kept for logging only
+    #[serde(skip)]
+    pub rejection: Rejection,
+    /// Kind of rejection, e.g. `out_of_range`
+    pub reason: &'static str,
+    /// Name of the range rule or ignore rule, e.g. `after_max_year`
+    pub rule: Option<String>,
+    /// Character count of the trimmed text, when it was read
+    pub length: Option<usize>,
+    pub shape: Option<ContentShape>,
+}
+
+impl RejectionSummary {
+    pub fn new(rejection: Rejection, text: &str) -> Self {
+        let text = text.trim();
+        let shape = if !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()) {
+            ContentShape::Digits
+        } else if text.contains('\n') {
+            ContentShape::MultiLine
+        } else {
+            ContentShape::SingleLine
+        };
+        Self {
+            reason: rejection.reason(),
+            rule: rejection.rule(),
+            rejection,
+            length: Some(text.chars().count()),
+            shape: Some(shape),
+        }
+    }
+
+    /// A rejection decided without reading the clipboard text
+    pub fn unread(rejection: Rejection) -> Self {
+        Self {
+            reason: rejection.reason(),
+            rule: rejection.rule(),
+            rejection,
+            length: None,
+            shape: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_hides_text() {
+        let summary = RejectionSummary::new(
+            Rejection::OutOfRange(RangeRejection::AfterMaxYear {
+                year: 2286,
+                max_year: 2050,
+            }),
+            " 9999999999\n",
+        );
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "reason": "out_of_range",
+                "rule": "after_max_year",
+                "length": 10,
+                "shape": "digits"
+            })
+        );
+        assert!(!json.to_string().contains("9999999999"));
+        assert!(!json.to_string().contains("2286"));
+
+        let window =
+            RejectionSummary::unread(Rejection::OutOfRange(RangeRejection::BeforeWindow {
+                seconds: 1234,
+            }));
+        assert_eq!(
+            serde_json::to_value(&window).unwrap(),
+            serde_json::json!({
+                "reason": "out_of_range",
+                "rule": "before_window",
+                "length": null,
+                "shape": null
+            })
+        );
+    }
+
+    #[test]
+    fn test_shapes() {
+        let shape = |text| RejectionSummary::new(Rejection::NotRecognized, text).shape;
+        assert_eq!(shape("hello world"), Some(ContentShape::SingleLine));
+        assert_eq!(shape("a\nb"), Some(ContentShape::MultiLine));
+        assert_eq!(RejectionSummary::unread(Rejection::Paused).shape, None);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::i18n::Language;
+
+/// Nominal unit lengths, matching the ones used for ISO 8601 durations
+const SECONDS_PER_MINUTE: i64 = 60;
+const SECONDS_PER_HOUR: i64 = 3600;
+const SECONDS_PER_DAY: i64 = 86_400;
+const DAYS_PER_MONTH: i64 = 30;
+const DAYS_PER_YEAR: i64 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+}
+
+/// Describe `target` relative to `now` (both in epoch seconds), e.g.
+/// "3 hours ago", "in 2 days", "3小时前" or "2天后"
+pub fn describe(target: i64, now: i64, language: Language) -> String {
+    let diff = target.saturating_sub(now);
+    let abs = diff.unsigned_abs() as i64;
+    let is_past = diff < 0;
+
+    if abs == 0 {
+        return match language {
+            Language::English => "now".to_string(),
+            Language::SimplifiedChinese => "现在".to_string(),
+        };
+    }
+
+    let days = abs / SECONDS_PER_DAY;
+    let (count, unit) = if days >= DAYS_PER_YEAR {
+        (days / DAYS_PER_YEAR, Unit::Year)
+    } else if days >= DAYS_PER_MONTH {
+        (days / DAYS_PER_MONTH, Unit::Month)
+    } else if days > 0 {
+        (days, Unit::Day)
+    } else if abs >= SECONDS_PER_HOUR {
+        (abs / SECONDS_PER_HOUR, Unit::Hour)
+    } else if abs >= SECONDS_PER_MINUTE {
+        (abs / SECONDS_PER_MINUTE, Unit::Minute)
+    } else {
+        (abs, Unit::Second)
+    };
+
+    match language {
+        Language::English => {
+            let name = english_unit(unit, count);
+            if is_past {
+                format!("{} {} ago", count, name)
+            } else {
+                format!("in {} {}", count, name)
+            }
+        }
+        Language::SimplifiedChinese => {
+            // Chinese has no plural forms, only a direction suffix
+            let suffix = if is_past { "前" } else { "后" };
+            format!("{}{}{}", count, chinese_unit(unit), suffix)
+        }
+    }
+}
+
+fn english_unit(unit: Unit, count: i64) -> &'static str {
+    let singular = count == 1;
+    match unit {
+        Unit::Second if singular => "second",
+        Unit::Second => "seconds",
+        Unit::Minute if singular => "minute",
+        Unit::Minute => "minutes",
+        Unit::Hour if singular => "hour",
+        Unit::Hour => "hours",
+        Unit::Day if singular => "day",
+        Unit::Day => "days",
+        Unit::Month if singular => "month",
+        Unit::Month => "months",
+        Unit::Year if singular => "year",
+        Unit::Year => "years",
+    }
+}
+
+fn chinese_unit(unit: Unit) -> &'static str {
+    match unit {
+        Unit::Second => "秒",
+        Unit::Minute => "分钟",
+        Unit::Hour => "小时",
+        Unit::Day => "天",
+        Unit::Month => "个月",
+        Unit::Year => "年",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_704_067_200;
+
+    #[test]
+    fn test_english_pluralization() {
+        assert_eq!(
+            describe(NOW - 3 * 3600, NOW, Language::English),
+            "3 hours ago"
+        );
+        assert_eq!(describe(NOW - 3600, NOW, Language::English), "1 hour ago");
+        assert_eq!(
+            describe(NOW + 2 * 86_400, NOW, Language::English),
+            "in 2 days"
+        );
+        assert_eq!(describe(NOW + 1, NOW, Language::English), "in 1 second");
+        assert_eq!(describe(NOW, NOW, Language::English), "now");
+    }
+
+    #[test]
+    fn test_simplified_chinese() {
+        let language = Language::SimplifiedChinese;
+        assert_eq!(describe(NOW - 3 * 3600, NOW, language), "3小时前");
+        assert_eq!(describe(NOW + 2 * 86_400, NOW, language), "2天后");
+        assert_eq!(describe(NOW - 400 * 86_400, NOW, language), "1年前");
+    }
+
+    #[test]
+    fn test_unit_boundaries() {
+        let language = Language::English;
+        assert_eq!(describe(NOW - 59, NOW, language), "59 seconds ago");
+        assert_eq!(describe(NOW - 60, NOW, language), "1 minute ago");
+        assert_eq!(describe(NOW - 45 * 86_400, NOW, language), "1 month ago");
+    }
+}
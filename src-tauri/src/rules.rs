@@ -0,0 +1,199 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Compiled programs above this size are rejected so a rule cannot stall the poller
+const MAX_REGEX_SIZE: usize = 1 << 20;
+
+/// A user-defined regex matched against copied text
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContentRule {
+    pub name: String,
+    pub pattern: String,
+}
+
+/// How often one rule decided the fate of a copy
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RuleHits {
+    pub name: String,
+    pub pattern: String,
+    pub hits: u64,
+}
+
+/// Hit counters for every rule, returned by the `get_rule_stats` command
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RuleStats {
+    /// Copies suppressed by each ignore rule
+    pub ignore: Vec<RuleHits>,
+    /// Copies let through by each allow rule
+    pub allow: Vec<RuleHits>,
+    /// Copies suppressed because no allow rule matched
+    pub not_allowed: u64,
+}
+
+/// Outcome of checking copied text against the rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    /// Suppressed by the ignore rule at this index
+    Ignored(usize),
+    /// Allow rules exist and none matched
+    NotAllowed,
+}
+
+struct CompiledRule {
+    rule: ContentRule,
+    regex: Regex,
+    hits: u64,
+}
+
+/// Ignore and allow rules, compiled once per config
+#[derive(Default)]
+pub struct RuleSet {
+    ignore: Vec<CompiledRule>,
+    allow: Vec<CompiledRule>,
+    not_allowed: u64,
+}
+
+/// Compile one rule's pattern
+pub fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .size_limit(MAX_REGEX_SIZE)
+        .build()
+}
+
+impl RuleSet {
+    /// Compile the rules, skipping invalid patterns. Counters carry over for
+    /// rules whose pattern is unchanged.
+    pub fn new(ignore: &[ContentRule], allow: &[ContentRule], previous: Option<&RuleSet>) -> Self {
+        let build = |rules: &[ContentRule], old: &[CompiledRule]| -> Vec<CompiledRule> {
+            rules
+                .iter()
+                .filter_map(|rule| {
+                    let regex = compile(&rule.pattern).ok()?;
+                    let hits = old
+                        .iter()
+                        .find(|o| o.rule.pattern == rule.pattern)
+                        .map_or(0, |o| o.hits);
+                    Some(CompiledRule {
+                        rule: rule.clone(),
+                        regex,
+                        hits,
+                    })
+                })
+                .collect()
+        };
+
+        Self {
+            ignore: build(ignore, previous.map_or(&[], |p| &p.ignore)),
+            allow: build(allow, previous.map_or(&[], |p| &p.allow)),
+            not_allowed: previous.map_or(0, |p| p.not_allowed),
+        }
+    }
+
+    /// Decide whether `text` may be parsed, counting the rule responsible.
+    /// Ignore rules win over allow rules.
+    pub fn evaluate(&mut self, text: &str) -> Verdict {
+        let text = text.trim();
+        if let Some(index) = self.ignore.iter().position(|r| r.regex.is_match(text)) {
+            self.ignore[index].hits += 1;
+            return Verdict::Ignored(index);
+        }
+        if self.allow.is_empty() {
+            return Verdict::Pass;
+        }
+        match self.allow.iter_mut().find(|r| r.regex.is_match(text)) {
+            Some(rule) => {
+                rule.hits += 1;
+                Verdict::Pass
+            }
+            None => {
+                self.not_allowed += 1;
+                Verdict::NotAllowed
+            }
+        }
+    }
+
+    /// Name of the ignore rule at `index`, for logging
+    pub fn ignore_name(&self, index: usize) -> &str {
+        self.ignore.get(index).map_or("", |r| r.rule.name.as_str())
+    }
+
+    pub fn stats(&self) -> RuleStats {
+        let hits = |rules: &[CompiledRule]| {
+            rules
+                .iter()
+                .map(|r| RuleHits {
+                    name: r.rule.name.clone(),
+                    pattern: r.rule.pattern.clone(),
+                    hits: r.hits,
+                })
+                .collect()
+        };
+        RuleStats {
+            ignore: hits(&self.ignore),
+            allow: hits(&self.allow),
+            not_allowed: self.not_allowed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, pattern: &str) -> ContentRule {
+        ContentRule {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_ignore_rules() {
+        let mut rules = RuleSet::new(&[rule("accounts", r"^88\d{8}$")], &[], None);
+        assert_eq!(rules.evaluate("8812345678"), Verdict::Ignored(0));
+        assert_eq!(rules.evaluate(" 8812345678\n"), Verdict::Ignored(0));
+        assert_eq!(rules.evaluate("1704067200"), Verdict::Pass);
+        assert_eq!(rules.stats().ignore[0].hits, 2);
+    }
+
+    #[test]
+    fn test_allow_rules() {
+        let mut rules = RuleSet::new(
+            &[rule("phones", r"^1[3-9]\d{9}$")],
+            &[rule("epoch", r"^\d{10}(\d{3})?$")],
+            None,
+        );
+        assert_eq!(rules.evaluate("1704067200"), Verdict::Pass);
+        assert_eq!(rules.evaluate("13812345678"), Verdict::Ignored(0));
+        assert_eq!(rules.evaluate("tomorrow 9am"), Verdict::NotAllowed);
+
+        let stats = rules.stats();
+        assert_eq!(stats.allow[0].hits, 1);
+        assert_eq!(stats.ignore[0].hits, 1);
+        assert_eq!(stats.not_allowed, 1);
+    }
+
+    #[test]
+    fn test_counters_survive_recompile() {
+        let mut rules = RuleSet::new(&[rule("a", "^1$"), rule("b", "^2$")], &[], None);
+        rules.evaluate("1");
+        rules.evaluate("2");
+        let rules = RuleSet::new(
+            &[rule("renamed", "^1$"), rule("b", "^3$")],
+            &[],
+            Some(&rules),
+        );
+        let stats = rules.stats();
+        assert_eq!(stats.ignore[0].hits, 1);
+        assert_eq!(stats.ignore[0].name, "renamed");
+        assert_eq!(stats.ignore[1].hits, 0);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_skipped() {
+        assert!(compile("(").is_err());
+        let rules = RuleSet::new(&[rule("broken", "("), rule("ok", "x")], &[], None);
+        assert_eq!(rules.stats().ignore.len(), 1);
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::Serialize;
+
+/// Languages and markups an instant can be copied as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeTarget {
+    Python,
+    JavaScript,
+    Sql,
+    Go,
+    Discord,
+}
+
+/// An instant rendered for one `CodeTarget`
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CodeSnippet {
+    /// Stable id passed back to `copy_result_as_code`, e.g. `python`
+    pub target: String,
+    pub text: String,
+}
+
+impl CodeTarget {
+    pub const ALL: [CodeTarget; 5] = [
+        CodeTarget::Python,
+        CodeTarget::JavaScript,
+        CodeTarget::Sql,
+        CodeTarget::Go,
+        CodeTarget::Discord,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            CodeTarget::Python => "python",
+            CodeTarget::JavaScript => "javascript",
+            CodeTarget::Sql => "sql",
+            CodeTarget::Go => "go",
+            CodeTarget::Discord => "discord",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|target| target.id() == id)
+    }
+
+    /// Render an epoch in milliseconds; sub-second precision is kept where the target allows
+    pub fn render(self, timestamp_milliseconds: i64) -> Option<String> {
+        let utc = DateTime::<Utc>::from_timestamp_millis(timestamp_milliseconds)?;
+        let seconds = utc.timestamp();
+        let millis = utc.timestamp_subsec_millis();
+
+        Some(match self {
+            CodeTarget::Python => {
+                let mut fields = vec![utc.year(), utc.month() as i32, utc.day() as i32];
+                if utc.num_seconds_from_midnight() != 0 || millis != 0 {
+                    fields.extend([utc.hour() as i32, utc.minute() as i32, utc.second() as i32]);
+                }
+                if millis != 0 {
+                    fields.push(millis as i32 * 1000);
+                }
+                let fields: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+                format!("datetime({}, tzinfo=timezone.utc)", fields.join(", "))
+            }
+            CodeTarget::JavaScript => format!("new Date({})", timestamp_milliseconds),
+            CodeTarget::Sql => {
+                let fraction = if millis != 0 {
+                    format!(".{:03}", millis)
+                } else {
+                    String::new()
+                };
+                format!(
+                    "TIMESTAMP '{}{}+00'",
+                    utc.format("%Y-%m-%d %H:%M:%S"),
+                    fraction
+                )
+            }
+            CodeTarget::Go => {
+                if millis != 0 {
+                    format!("time.UnixMilli({})", timestamp_milliseconds)
+                } else {
+                    format!("time.Unix({}, 0)", seconds)
+                }
+            }
+            CodeTarget::Discord => format!("<t:{}:F>", seconds),
+        })
+    }
+}
+
+/// Every target rendered for one instant, in `CodeTarget::ALL` order
+pub fn render_all(timestamp_milliseconds: i64) -> Vec<CodeSnippet> {
+    CodeTarget::ALL
+        .iter()
+        .filter_map(|target| {
+            Some(CodeSnippet {
+                target: target.id().to_string(),
+                text: target.render(timestamp_milliseconds)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(target: CodeTarget, millis: i64) -> String {
+        target.render(millis).unwrap()
+    }
+
+    #[test]
+    fn test_whole_seconds() {
+        let millis = 1_704_067_200_000;
+        assert_eq!(
+            render(CodeTarget::Python, millis),
+            "datetime(2024, 1, 1, tzinfo=timezone.utc)"
+        );
+        assert_eq!(
+            render(CodeTarget::JavaScript, millis),
+            "new Date(1704067200000)"
+        );
+        assert_eq!(
+            render(CodeTarget::Sql, millis),
+            "TIMESTAMP '2024-01-01 00:00:00+00'"
+        );
+        assert_eq!(render(CodeTarget::Go, millis), "time.Unix(1704067200, 0)");
+        assert_eq!(render(CodeTarget::Discord, millis), "<t:1704067200:F>");
+    }
+
+    #[test]
+    fn test_sub_second() {
+        let millis = 1_704_097_845_123;
+        assert_eq!(
+            render(CodeTarget::Python, millis),
+            "datetime(2024, 1, 1, 8, 30, 45, 123000, tzinfo=timezone.utc)"
+        );
+        assert_eq!(
+            render(CodeTarget::Sql, millis),
+            "TIMESTAMP '2024-01-01 08:30:45.123+00'"
+        );
+        assert_eq!(
+            render(CodeTarget::Go, millis),
+            "time.UnixMilli(1704097845123)"
+        );
+        assert_eq!(render(CodeTarget::Discord, millis), "<t:1704097845:F>");
+    }
+
+    #[test]
+    fn test_render_all_ids() {
+        let snippets = render_all(1_704_067_200_000);
+        assert_eq!(snippets.len(), CodeTarget::ALL.len());
+        for snippet in snippets {
+            assert!(CodeTarget::from_id(&snippet.target).is_some());
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// How long a write made by the app stays recognizable when it shows up in the poller
+pub const SELF_WRITE_WINDOW: Duration = Duration::from_millis(2000);
+
+/// Writes made by the app itself, remembered by content hash for a short window
+/// so that the clipboard poller does not decode them.
+#[derive(Debug, Default)]
+pub struct WriteTracker {
+    writes: Vec<(u64, Instant)>,
+}
+
+impl WriteTracker {
+    /// Remember that the app is about to write `text`
+    pub fn record(&mut self, text: &str, now: Instant) {
+        self.expire(now);
+        self.writes.push((content_hash(text), now));
+    }
+
+    /// Whether `text` is one of the app's own recent writes. A match is
+    /// consumed so that the user copying the same text later still counts.
+    pub fn take(&mut self, text: &str, now: Instant) -> bool {
+        self.expire(now);
+        let hash = content_hash(text);
+        match self.writes.iter().position(|(h, _)| *h == hash) {
+            Some(index) => {
+                self.writes.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expire(&mut self, now: Instant) {
+        self.writes
+            .retain(|(_, at)| now.saturating_duration_since(*at) <= SELF_WRITE_WINDOW);
+    }
+}
+
+/// Hash ignoring carriage returns and trailing whitespace, which some
+/// platforms add when text round-trips through the clipboard
+fn content_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for c in text.trim_end().chars().filter(|c| *c != '\r') {
+        c.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_own_write_is_consumed() {
+        let start = Instant::now();
+        let mut tracker = WriteTracker::default();
+        tracker.record("1704067200", start);
+
+        assert!(!tracker.take("1704067201", start));
+        assert!(tracker.take("1704067200", start + Duration::from_millis(350)));
+        // The user copying the same value afterwards is not suppressed
+        assert!(!tracker.take("1704067200", start + Duration::from_millis(700)));
+    }
+
+    #[test]
+    fn test_window_expires() {
+        let start = Instant::now();
+        let mut tracker = WriteTracker::default();
+        tracker.record("1704067200", start);
+        assert!(!tracker.take("1704067200", start + SELF_WRITE_WINDOW * 2));
+    }
+
+    #[test]
+    fn test_platform_line_endings() {
+        let start = Instant::now();
+        let mut tracker = WriteTracker::default();
+        tracker.record("a\nb", start);
+        assert!(tracker.take("a\r\nb\r\n", start));
+    }
+}
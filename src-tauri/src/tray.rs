@@ -1,8 +1,8 @@
-use crate::ClipboardMonitor;
-use log::info;
+use crate::{ClipboardMonitor, NowFormat};
+use log::{error, info};
 use std::sync::{Arc, Mutex};
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconEvent},
     AppHandle, Manager,
 };
@@ -17,6 +17,11 @@ fn get_tray_text(locale: &str) -> TrayTexts {
             resume: "恢复",
             settings: "设置...",
             quit: "退出 Timesdump",
+            copy_now: "复制当前时间",
+            restore: "恢复原剪贴板内容",
+            now_configured: "按时间格式",
+            history: "历史记录",
+            history_empty: "暂无记录",
         }
     } else {
         TrayTexts {
@@ -26,6 +31,11 @@ fn get_tray_text(locale: &str) -> TrayTexts {
             resume: "Resume",
             settings: "Settings...",
             quit: "Quit Timesdump",
+            copy_now: "Copy Current Time",
+            restore: "Restore Original Clipboard",
+            now_configured: "Time Format",
+            history: "History",
+            history_empty: "No History Yet",
         }
     }
 }
@@ -37,12 +47,125 @@ struct TrayTexts {
     resume: &'static str,
     settings: &'static str,
     quit: &'static str,
+    copy_now: &'static str,
+    restore: &'static str,
+    now_configured: &'static str,
+    history: &'static str,
+    history_empty: &'static str,
 }
 
+/// Menu item ids for the "Copy current time" entries
+const COPY_NOW_PREFIX: &str = "copy_now:";
+
+/// Menu item ids for history entries, followed by the entry id
+const HISTORY_PREFIX: &str = "history:";
+
+/// How many history entries the tray submenu lists
+const HISTORY_MENU_ENTRIES: usize = 10;
+
 /// Stores references to menu items that need to be updated
 pub struct TrayMenuState {
     pub status_item: MenuItem<tauri::Wry>,
     pub pause_item: MenuItem<tauri::Wry>,
+    pub copy_now_menu: Submenu<tauri::Wry>,
+    pub history_menu: Submenu<tauri::Wry>,
+}
+
+/// Fill the "Copy current time" submenu with the time format and the output formats
+fn fill_copy_now_menu(
+    app: &AppHandle,
+    menu: &Submenu<tauri::Wry>,
+    texts: &TrayTexts,
+) -> tauri::Result<()> {
+    for item in menu.items()? {
+        menu.remove(&item)?;
+    }
+
+    let formats = app
+        .try_state::<Arc<ClipboardMonitor>>()
+        .and_then(|monitor| monitor.with_parser(|parser| parser.now_formats()))
+        .unwrap_or_else(|| vec![NowFormat::Configured]);
+    for format in formats {
+        let label = match &format {
+            NowFormat::Configured => texts.now_configured.to_string(),
+            NowFormat::Output(name) => name.clone(),
+        };
+        let item = MenuItem::with_id(
+            app,
+            format!("{}{}", COPY_NOW_PREFIX, format.id()),
+            label,
+            true,
+            None::<&str>,
+        )?;
+        menu.append(&item)?;
+    }
+    Ok(())
+}
+
+/// Re-list the "Copy current time" submenu after the output formats changed.
+/// Must run on the main thread.
+pub fn refresh_copy_now_menu(app: &AppHandle) {
+    let Some(menu_state) = app.try_state::<Mutex<TrayMenuState>>() else {
+        return;
+    };
+    let Ok(state) = menu_state.lock() else {
+        return;
+    };
+    let locale = sys_locale::get_locale().unwrap_or_else(|| "en-US".to_string());
+    if let Err(e) = fill_copy_now_menu(app, &state.copy_now_menu, &get_tray_text(&locale)) {
+        error!("Failed to update copy current time menu: {}", e);
+    }
+}
+
+/// Fill the history submenu with the newest entries, replacing what it listed
+fn fill_history_menu(
+    app: &AppHandle,
+    menu: &Submenu<tauri::Wry>,
+    texts: &TrayTexts,
+) -> tauri::Result<()> {
+    for item in menu.items()? {
+        menu.remove(&item)?;
+    }
+
+    let entries = app
+        .try_state::<Arc<ClipboardMonitor>>()
+        .map(|monitor| monitor.recent_history(HISTORY_MENU_ENTRIES))
+        .unwrap_or_default();
+    if entries.is_empty() {
+        let empty = MenuItem::with_id(
+            app,
+            "history_empty",
+            texts.history_empty,
+            false,
+            None::<&str>,
+        )?;
+        return menu.append(&empty);
+    }
+    for entry in entries {
+        let item = MenuItem::with_id(
+            app,
+            format!("{}{}", HISTORY_PREFIX, entry.id),
+            entry.menu_label(),
+            true,
+            None::<&str>,
+        )?;
+        menu.append(&item)?;
+    }
+    Ok(())
+}
+
+/// Re-list the history submenu after a new entry was recorded. Must run on the main thread.
+pub fn refresh_history_menu(app: &AppHandle) {
+    let Some(menu_state) = app.try_state::<Mutex<TrayMenuState>>() else {
+        return;
+    };
+    let Ok(state) = menu_state.lock() else {
+        return;
+    };
+    let locale = sys_locale::get_locale().unwrap_or_else(|| "en-US".to_string());
+    if let Err(e) = fill_history_menu(app, &state.history_menu, &get_tray_text(&locale)) {
+        error!("Failed to update history menu: {}", e);
+    }
 }
 
 pub fn setup_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
@@ -55,12 +178,22 @@ pub fn setup_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>
     let pause_item = MenuItem::with_id(app, "pause", texts.pause, true, None::<&str>)?;
     let settings_item = MenuItem::with_id(app, "settings", texts.settings, true, None::<&str>)?;
     let separator = PredefinedMenuItem::separator(app)?;
+
+    let copy_now_menu = Submenu::new(app, texts.copy_now, true)?;
+    fill_copy_now_menu(app, &copy_now_menu, &texts)?;
+
+    let history_menu = Submenu::new(app, texts.history, true)?;
+    fill_history_menu(app, &history_menu, &texts)?;
+
+    let restore_item = MenuItem::with_id(app, "restore", texts.restore, true, None::<&str>)?;
     let quit_item = MenuItem::with_id(app, "quit", texts.quit, true, None::<&str>)?;
 
     // Store menu items for later updates
     app.manage(Mutex::new(TrayMenuState {
         status_item: status_item.clone(),
         pause_item: pause_item.clone(),
+        copy_now_menu: copy_now_menu.clone(),
+        history_menu: history_menu.clone(),
     }));
 
     // Create the menu
@@ -69,6 +202,9 @@ pub fn setup_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>
         &[
             &status_item,
             &separator,
+            &copy_now_menu,
+            &history_menu,
+            &restore_item,
             &pause_item,
             &settings_item,
             &PredefinedMenuItem::separator(app)?,
@@ -123,11 +259,54 @@ pub fn setup_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>
                 let _ = window.set_focus();
             }
         }
+        "restore" => {
+            if let Some(monitor) = app.try_state::<Arc<ClipboardMonitor>>() {
+                match monitor.restore_original() {
+                    Ok(true) => info!("Restored original clipboard text"),
+                    Ok(false) => info!("No replaced clipboard text to restore"),
+                    Err(e) => error!("Failed to restore clipboard: {}", e),
+                }
+            }
+        }
         "quit" => {
             info!("Quitting Timesdump");
             app.exit(0);
         }
-        _ => {}
+        id if id.starts_with(HISTORY_PREFIX) => {
+            let Some(entry_id) = id
+                .strip_prefix(HISTORY_PREFIX)
+                .and_then(|entry_id| entry_id.parse().ok())
+            else {
+                return;
+            };
+            if let Some(monitor) = app.try_state::<Arc<ClipboardMonitor>>() {
+                let Some(entry) = monitor.history_entry(entry_id) else {
+                    return;
+                };
+                if let Err(e) = monitor.write_text(entry.copy_text()) {
+                    error!("Failed to copy history entry: {}", e);
+                }
+            }
+        }
+        id => {
+            let Some(format) = id
+                .strip_prefix(COPY_NOW_PREFIX)
+                .and_then(NowFormat::from_id)
+            else {
+                return;
+            };
+            if let Some(monitor) = app.try_state::<Arc<ClipboardMonitor>>() {
+                let Some(text) = monitor
+                    .with_parser(|parser| parser.format_now(&format))
+                    .flatten()
+                else {
+                    return;
+                };
+                if let Err(e) = monitor.write_text(&text) {
+                    error!("Failed to copy current time: {}", e);
+                }
+            }
+        }
     });
 
     tray.on_tray_icon_event(|tray, event| {
@@ -0,0 +1,145 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Time zone used to render decoded instants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Zone {
+    #[default]
+    Utc,
+    /// The system time zone
+    Local,
+    /// An IANA zone such as `Asia/Shanghai`
+    Named(Tz),
+    /// A fixed offset such as `+08:00`
+    Fixed(FixedOffset),
+}
+
+impl Zone {
+    /// Parse a zone setting: `UTC`, `Local`, an IANA name or a `±HH:MM` offset
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("utc") || value.eq_ignore_ascii_case("z") {
+            return Some(Zone::Utc);
+        }
+        if value.eq_ignore_ascii_case("local") {
+            return Some(Zone::Local);
+        }
+        if let Some(offset) = parse_offset(value) {
+            return Some(Zone::Fixed(offset));
+        }
+        value.parse::<Tz>().ok().map(Zone::Named)
+    }
+
+    /// Convert an epoch in seconds into this zone
+    pub fn at(&self, timestamp_seconds: i64) -> Option<DateTime<FixedOffset>> {
+        let utc = Utc.timestamp_opt(timestamp_seconds, 0).single()?;
+        Some(self.from_utc(&utc))
+    }
+
+    /// Convert a UTC instant into this zone
+    pub fn from_utc(&self, utc: &DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self {
+            Zone::Utc => utc.fixed_offset(),
+            Zone::Local => utc.with_timezone(&Local).fixed_offset(),
+            Zone::Named(tz) => utc.with_timezone(tz).fixed_offset(),
+            Zone::Fixed(offset) => utc.with_timezone(offset),
+        }
+    }
+
+    /// Interpret a wall-clock time in this zone. Ambiguous times resolve to
+    /// the earlier instant; times skipped by a DST gap are rejected.
+    pub fn from_local(&self, naive: &NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Zone::Utc => Some(Utc.from_utc_datetime(naive).fixed_offset()),
+            Zone::Local => Local
+                .from_local_datetime(naive)
+                .earliest()
+                .map(|dt| dt.fixed_offset()),
+            Zone::Named(tz) => tz
+                .from_local_datetime(naive)
+                .earliest()
+                .map(|dt| dt.fixed_offset()),
+            Zone::Fixed(offset) => offset.from_local_datetime(naive).single(),
+        }
+    }
+}
+
+/// Parse `+08:00`, `+0800`, `-05` or `UTC+8` into a fixed offset
+pub fn parse_offset(value: &str) -> Option<FixedOffset> {
+    let value = value
+        .strip_prefix("UTC")
+        .or_else(|| value.strip_prefix("GMT"))
+        .unwrap_or(value);
+    let (sign, digits) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => return None,
+    };
+
+    let digits: String = digits.chars().filter(|c| *c != ':').collect();
+    if digits.is_empty() || digits.len() > 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        3 => (
+            digits[..1].parse::<i32>().ok()?,
+            digits[1..].parse::<i32>().ok()?,
+        ),
+        _ => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+    };
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_parsing() {
+        assert_eq!(Zone::parse("utc"), Some(Zone::Utc));
+        assert_eq!(Zone::parse("Local"), Some(Zone::Local));
+        assert!(matches!(Zone::parse("Europe/Berlin"), Some(Zone::Named(_))));
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        assert_eq!(Zone::parse("+08:00"), Some(Zone::Fixed(offset)));
+        assert_eq!(Zone::parse("+0800"), Some(Zone::Fixed(offset)));
+        assert_eq!(Zone::parse("Mars/Olympus"), None);
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        let offset = |seconds| FixedOffset::east_opt(seconds);
+        assert_eq!(parse_offset("UTC+8"), offset(8 * 3600));
+        assert_eq!(parse_offset("GMT-05"), offset(-5 * 3600));
+        assert_eq!(parse_offset("+530"), offset(5 * 3600 + 30 * 60));
+        assert_eq!(parse_offset("-03:30"), offset(-(3 * 3600 + 30 * 60)));
+        assert_eq!(parse_offset("+24:00"), None);
+        assert_eq!(parse_offset("0800"), None);
+    }
+
+    #[test]
+    fn test_from_utc() {
+        let at = |zone: &str| Zone::parse(zone).unwrap().at(1_704_067_200).unwrap();
+        assert_eq!(at("UTC").to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        assert_eq!(at("Asia/Kolkata").to_rfc3339(), "2024-01-01T05:30:00+05:30");
+        assert_eq!(at("-03:30").to_rfc3339(), "2023-12-31T20:30:00-03:30");
+    }
+
+    #[test]
+    fn test_from_local() {
+        let berlin = Zone::parse("Europe/Berlin").unwrap();
+        let naive = |value| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap();
+        // 02:30 was skipped when clocks sprang forward
+        assert_eq!(berlin.from_local(&naive("2024-03-31 02:30")), None);
+        // 02:30 happened twice when clocks fell back; the earlier one wins
+        let ambiguous = berlin.from_local(&naive("2024-10-27 02:30")).unwrap();
+        assert_eq!(ambiguous.to_rfc3339(), "2024-10-27T02:30:00+02:00");
+    }
+}
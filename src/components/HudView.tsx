@@ -9,46 +9,67 @@ const DEFAULT_DISPLAY_DURATION_MS = 3000;
 /** Shorter duration when resuming from hover */
 const HOVER_RESUME_DURATION_MS = 2000;
 
-/** Constants for relative time calculation */
-const DAYS_PER_MONTH = 30;
-const DAYS_PER_YEAR = 365;
+/** Short labels for the copy-as-code targets */
+const CODE_TARGET_LABELS: Record<string, string> = {
+  python: "Py",
+  javascript: "JS",
+  sql: "SQL",
+  go: "Go",
+  discord: "Discord",
+};
+
+interface DurationInfo {
+  human: string;
+  total_seconds: number;
+  total_milliseconds: number;
+}
+
+interface CalendarInfo {
+  iso_weekday: number;
+  iso_week_year: number;
+  iso_week: number;
+  ordinal: number;
+  quarter: number;
+  is_weekend: boolean;
+}
+
+interface AuthorTime {
+  formatted_time: string;
+  offset: string;
+  identity: string | null;
+}
+
+interface CronInfo {
+  description: string;
+  next_runs: string[];
+}
+
+interface CodeSnippet {
+  target: string;
+  text: string;
+}
+
+interface FormattedVariant {
+  name: string;
+  text: string;
+}
 
 interface HudPayload {
+  kind: "timestamp" | "duration" | "cron";
   formatted_time: string;
   raw_value: string;
   timestamp_seconds: number;
+  timestamp_milliseconds: number;
   is_milliseconds: boolean;
-}
-
-/** Calculate relative time from timestamp */
-function calculateRelativeTime(timestampSeconds: number, t: (key: string, options?: any) => string): string {
-  const now = Math.floor(Date.now() / 1000);
-  const diffSeconds = timestampSeconds - now;
-  const absDiff = Math.abs(diffSeconds);
-  const isPast = diffSeconds < 0;
-
-  // Calculate time units
-  const seconds = Math.floor(absDiff);
-  const minutes = Math.floor(seconds / 60);
-  const hours = Math.floor(minutes / 60);
-  const days = Math.floor(hours / 24);
-  const months = Math.floor(days / DAYS_PER_MONTH);
-  const years = Math.floor(days / DAYS_PER_YEAR);
-
-  // Format the relative time string with i18n
-  if (years > 0) {
-    return t(isPast ? "hud.relative.yearsAgo" : "hud.relative.yearsLater", { count: years });
-  } else if (months > 0) {
-    return t(isPast ? "hud.relative.monthsAgo" : "hud.relative.monthsLater", { count: months });
-  } else if (days > 0) {
-    return t(isPast ? "hud.relative.daysAgo" : "hud.relative.daysLater", { count: days });
-  } else if (hours > 0) {
-    return t(isPast ? "hud.relative.hoursAgo" : "hud.relative.hoursLater", { count: hours });
-  } else if (minutes > 0) {
-    return t(isPast ? "hud.relative.minutesAgo" : "hud.relative.minutesLater", { count: minutes });
-  } else {
-    return t(isPast ? "hud.relative.secondsAgo" : "hud.relative.secondsLater", { count: seconds });
-  }
+  relative_time: string | null;
+  calendar: CalendarInfo | null;
+  alt_calendar: string | null;
+  duration: DurationInfo | null;
+  author_time: AuthorTime | null;
+  detector: string | null;
+  cron: CronInfo | null;
+  code: CodeSnippet[];
+  variants: FormattedVariant[];
 }
 
 export default function HudView() {
@@ -58,6 +79,7 @@ export default function HudView() {
   const [isHovered, setIsHovered] = useState(false);
   const [isPinned, setIsPinned] = useState(false);
   const [copySuccess, setCopySuccess] = useState(false);
+  const [copiedTarget, setCopiedTarget] = useState<string | null>(null);
   const [displayDuration, setDisplayDuration] = useState(DEFAULT_DISPLAY_DURATION_MS);
   
   // Use refs to avoid stale closures and prevent effect re-runs
@@ -147,6 +169,59 @@ export default function HudView() {
     }
   }, [payload, copySuccess]);
 
+  // Copy the instant as a code literal or chat markup
+  const handleCopyCode = useCallback(async (target: string) => {
+    if (!payload) return;
+    try {
+      await invoke("copy_result_as_code", {
+        timestampMilliseconds: payload.timestamp_milliseconds,
+        target,
+      });
+      setCopiedTarget(target);
+      setTimeout(() => setCopiedTarget(null), 1500);
+    } catch (error) {
+      console.error("Failed to copy as code:", error);
+    }
+  }, [payload]);
+
+  // Copy the instant in one of the configured output formats
+  const handleCopyVariant = useCallback(async (name: string) => {
+    if (!payload) return;
+    try {
+      await invoke("copy_variant", {
+        name,
+        timestampMilliseconds: payload.timestamp_milliseconds,
+      });
+      setCopiedTarget(name);
+      setTimeout(() => setCopiedTarget(null), 1500);
+    } catch (error) {
+      console.error("Failed to copy variant:", error);
+    }
+  }, [payload]);
+
+  // Hand the instant to the event editor in the settings window; the HUD
+  // never takes keyboard focus, so the title can't be typed here
+  const handleCalendarEdit = useCallback(async () => {
+    if (!payload) return;
+    try {
+      await invoke("edit_calendar_event", {
+        draft: {
+          event: {
+            title: t("hud.eventTitle", { raw: payload.raw_value.trim() }),
+            timestamp_milliseconds: payload.timestamp_milliseconds,
+            alarm_minutes: null,
+            description: payload.raw_value.trim(),
+          },
+          formatted_time: payload.formatted_time,
+        },
+      });
+      setCopiedTarget("calendar");
+      setTimeout(() => setCopiedTarget(null), 1500);
+    } catch (error) {
+      console.error("Failed to open calendar event editor:", error);
+    }
+  }, [payload, t]);
+
   // Handle pin toggle
   const handlePinToggle = useCallback(() => {
     setIsPinned((prev) => {
@@ -191,23 +266,132 @@ export default function HudView() {
         "
       >
         {/* Main time display */}
-        <div className="text-[22px] font-medium tracking-tight text-black/85 dark:text-white/90 text-center font-mono">
+        <div
+          className={`${payload.kind === "cron" ? "text-[15px]" : "text-[22px] font-mono"} font-medium tracking-tight text-black/85 dark:text-white/90 text-center`}
+        >
           {payload.formatted_time}
         </div>
         
-        {/* Relative time display */}
+        {/* Relative time display, totals for a duration, or the next cron run */}
         <div className="mt-1 text-[13px] text-black/60 dark:text-white/65 tracking-wide">
-          {calculateRelativeTime(payload.timestamp_seconds, t)}
+          {payload.duration
+            ? t("hud.durationTotals", {
+                seconds: payload.duration.total_seconds,
+                milliseconds: payload.duration.total_milliseconds,
+              })
+            : payload.cron && payload.cron.next_runs.length > 0
+              ? t("hud.cronNext", {
+                  time: payload.cron.next_runs[0],
+                  relative: payload.relative_time,
+                })
+              : payload.relative_time}
         </div>
         
-        {/* Metadata row */}
+        {/* Time in the author's own offset */}
+        {payload.author_time && (
+          <div className="mt-0.5 text-[12px] text-black/50 dark:text-white/55">
+            {t("hud.authorTime", {
+              time: payload.author_time.formatted_time,
+              offset: payload.author_time.offset,
+            })}
+          </div>
+        )}
+
+        {/* Custom detector that decoded the copy */}
+        {payload.detector && (
+          <div className="mt-0.5 max-w-full truncate text-[12px] text-black/50 dark:text-white/55">
+            {t("hud.detector", { label: payload.detector })}
+          </div>
+        )}
+
+        {/* Upcoming cron fire times */}
+        {payload.cron && payload.cron.next_runs.length > 1 && (
+          <div
+            className="mt-0.5 max-w-full truncate text-[12px] text-black/50 dark:text-white/55 font-mono"
+            title={payload.cron.next_runs.join("\n")}
+          >
+            {t("hud.cronThen", { times: payload.cron.next_runs.slice(1).join(" · ") })}
+          </div>
+        )}
+
+        {/* Secondary calendar */}
+        {payload.alt_calendar && (
+          <div className="mt-0.5 text-[12px] text-black/50 dark:text-white/55">
+            {payload.alt_calendar}
+          </div>
+        )}
+
+        {/* "Copy as" choices replace the metadata row while hovered */}
+        {isHovered && (payload.variants.length > 0 || payload.code.length > 0) ? (
+          <div className="mt-1.5 flex gap-1 max-w-full overflow-x-auto pointer-events-auto">
+            {payload.variants.map((variant) => (
+              <button
+                key={`variant-${variant.name}`}
+                onClick={() => handleCopyVariant(variant.name)}
+                title={variant.text}
+                className={`
+                  shrink-0 px-1.5 py-0.5 rounded text-[11px] transition-colors
+                  ${copiedTarget === variant.name
+                    ? "bg-green-500/80 text-white"
+                    : "bg-black/5 dark:bg-white/10 hover:bg-black/10 dark:hover:bg-white/20 text-black/60 dark:text-white/70"
+                  }
+                `}
+              >
+                {variant.name}
+              </button>
+            ))}
+            {payload.code.map((snippet) => (
+              <button
+                key={snippet.target}
+                onClick={() => handleCopyCode(snippet.target)}
+                title={snippet.text}
+                className={`
+                  shrink-0 px-1.5 py-0.5 rounded text-[11px] font-mono transition-colors
+                  ${copiedTarget === snippet.target
+                    ? "bg-green-500/80 text-white"
+                    : "bg-black/5 dark:bg-white/10 hover:bg-black/10 dark:hover:bg-white/20 text-black/60 dark:text-white/70"
+                  }
+                `}
+              >
+                {CODE_TARGET_LABELS[snippet.target] ?? snippet.target}
+              </button>
+            ))}
+            {payload.kind === "timestamp" && (
+              <button
+                onClick={handleCalendarEdit}
+                title={t("hud.addToCalendar")}
+                className={`
+                  shrink-0 px-1.5 py-0.5 rounded text-[11px] transition-colors
+                  ${copiedTarget === "calendar"
+                    ? "bg-green-500/80 text-white"
+                    : "bg-black/5 dark:bg-white/10 hover:bg-black/10 dark:hover:bg-white/20 text-black/60 dark:text-white/70"
+                  }
+                `}
+              >
+                .ics
+              </button>
+            )}
+          </div>
+        ) : (
         <div className="mt-1.5 text-[13px] text-black/45 dark:text-white/50">
           <span className="font-mono">
             {payload.raw_value.length > 13 
               ? payload.raw_value.slice(0, 13) 
               : payload.raw_value}
           </span>
+          {payload.calendar && (
+            <span>
+              {" · "}
+              {t("hud.calendar", {
+                year: payload.calendar.iso_week_year,
+                week: String(payload.calendar.iso_week).padStart(2, "0"),
+                quarter: payload.calendar.quarter,
+                ordinal: payload.calendar.ordinal,
+              })}
+            </span>
+          )}
         </div>
+        )}
       </div>
 
       {/* Action buttons */}
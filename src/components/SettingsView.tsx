@@ -1,15 +1,123 @@
 import { useEffect, useState, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWindow } from "@tauri-apps/api/window";
+import { listen } from "@tauri-apps/api/event";
 import { enable, disable, isEnabled } from "@tauri-apps/plugin-autostart";
 import { useTranslation } from "react-i18next";
 
+interface OutputFormat {
+  name: string;
+  pattern: string;
+}
+
+interface ContentRule {
+  name: string;
+  pattern: string;
+}
+
+interface RuleStats {
+  ignore: { name: string; pattern: string; hits: number }[];
+  allow: { name: string; pattern: string; hits: number }[];
+  not_allowed: number;
+}
+
+type RuleField = "ignore_rules" | "allow_rules";
+
+interface CustomDetector {
+  label: string;
+  pattern: string;
+  origin: string;
+  unit: string;
+}
+
+const TIME_UNITS = ["seconds", "milliseconds", "microseconds", "nanoseconds", "minutes", "hours", "days"];
+
+interface RecentWindow {
+  before_seconds: number;
+  after_seconds: number;
+}
+
+const SECONDS_PER_DAY = 86400;
+
+interface HistoryEntry {
+  id: number;
+  raw_value: string;
+  interpretation: { type: string; detector?: string };
+  formatted_time: string;
+  timestamp_milliseconds: number;
+  detected_at: number;
+  pinned: boolean;
+}
+
+interface HistoryFilter {
+  text: string;
+  /** `YYYY-MM-DD` from the date inputs, empty for no bound */
+  from: string;
+  to: string;
+  detector: string;
+  pinned_only: boolean;
+}
+
+const HISTORY_TYPES = ["timestamp", "git_date", "duration", "cron"];
+
+const EXPORT_FORMATS = [
+  { value: "csv", label: "CSV" },
+  { value: "json_lines", label: "JSON Lines" },
+  { value: "markdown", label: "Markdown" },
+];
+
+/** A decoded instant sent from the HUD to be saved as an .ics event */
+interface EventDraft {
+  event: {
+    title: string;
+    timestamp_milliseconds: number;
+    alarm_minutes: number | null;
+    description: string | null;
+  };
+  formatted_time: string;
+}
+
+/** Reminder choices for calendar events, in minutes before the event */
+const ALARM_OPTIONS = [
+  { value: "", labelKey: "settings.alarmNone" },
+  { value: "0", labelKey: "settings.alarmAtStart" },
+  { value: "15", labelKey: "settings.alarm15Minutes" },
+  { value: "60", labelKey: "settings.alarm1Hour" },
+  { value: "1440", labelKey: "settings.alarm1Day" },
+];
+
+interface SettingsError {
+  kind: "invalid_format" | "invalid_rule" | "invalid_detector" | "storage";
+  field?: string;
+  token?: string;
+  position?: number;
+  message?: string;
+}
+
 interface Settings {
   min_year: number;
   max_year: number;
+  recent_window: RecentWindow | null;
+  ignore_rules: ContentRule[];
+  allow_rules: ContentRule[];
+  custom_detectors: CustomDetector[];
   display_duration_ms: number;
   time_format: string;
+  format_dialect: string;
+  format_locale: string;
+  output_formats: OutputFormat[];
   hud_position: string;
+  timezone: string;
+  calendar_system: string;
+  date_literals: boolean;
+  date_order: string;
+  duration_mode: boolean;
+  natural_language: boolean;
+  date_math: boolean;
+  auto_replace: boolean;
+  cron_mode: boolean;
+  cron_runs: number;
+  history_limit: number;
 }
 
 const TIME_FORMATS = [
@@ -21,6 +129,27 @@ const TIME_FORMATS = [
   { value: "%H:%M:%S", label: "HH:mm:ss" },
 ];
 
+const CUSTOM_FORMAT = "custom";
+
+// Presets above are strftime; the other dialects are typed into the custom field
+// Month and weekday names are shown in the language's own words
+const FORMAT_LOCALES = [
+  { value: "zh-CN", label: "中文" },
+  { value: "en-US", label: "English" },
+  { value: "ja-JP", label: "日本語" },
+  { value: "ko-KR", label: "한국어" },
+  { value: "de-DE", label: "Deutsch" },
+  { value: "fr-FR", label: "Français" },
+  { value: "es-ES", label: "Español" },
+];
+
+const FORMAT_DIALECTS = [
+  { value: "strftime", label: "strftime (%Y-%m-%d)" },
+  { value: "moment", label: "moment / Day.js (YYYY-MM-DD)" },
+  { value: "java", label: "Java (yyyy-MM-dd)" },
+  { value: "dotnet", label: ".NET (yyyy-MM-dd)" },
+];
+
 const HUD_POSITIONS = [
   { value: "top_center", labelKey: "settings.hudPositionTopCenter" },
   { value: "top_left", labelKey: "settings.hudPositionTopLeft" },
@@ -30,18 +159,106 @@ const HUD_POSITIONS = [
   { value: "bottom_right", labelKey: "settings.hudPositionBottomRight" },
 ];
 
+const DATE_ORDERS = [
+  { value: "auto", labelKey: "settings.dateOrderAuto" },
+  { value: "dmy", labelKey: "settings.dateOrderDmy" },
+  { value: "mdy", labelKey: "settings.dateOrderMdy" },
+];
+
+const CALENDAR_SYSTEMS = [
+  { value: "none", labelKey: "settings.calendarSystemNone" },
+  { value: "chinese", labelKey: "settings.calendarSystemChinese" },
+  { value: "persian", labelKey: "settings.calendarSystemPersian" },
+  { value: "islamic", labelKey: "settings.calendarSystemIslamic" },
+];
+
+/** Pill-shaped on/off switch used for boolean settings */
+function ToggleSwitch({ checked, onToggle }: { checked: boolean; onToggle: () => void }) {
+  return (
+    <button
+      onClick={onToggle}
+      style={{
+        width: 44,
+        height: 26,
+        borderRadius: 13,
+        border: 'none',
+        background: checked ? '#22c55e' : '#e2e8f0',
+        position: 'relative',
+        cursor: 'pointer',
+        transition: 'background 0.2s'
+      }}
+    >
+      <span
+        style={{
+          position: 'absolute',
+          top: 2,
+          left: checked ? 20 : 2,
+          width: 22,
+          height: 22,
+          borderRadius: 11,
+          background: 'white',
+          boxShadow: '0 1px 3px rgba(0,0,0,0.2)',
+          transition: 'left 0.2s'
+        }}
+      />
+    </button>
+  );
+}
+
 export default function SettingsView() {
   const { t } = useTranslation();
   const [settings, setSettings] = useState<Settings>({
     min_year: 1990,
     max_year: 2050,
+    recent_window: null,
+    ignore_rules: [],
+    allow_rules: [],
+    custom_detectors: [],
     display_duration_ms: 5000,
     time_format: "%Y-%m-%d %H:%M:%S",
+    format_dialect: "strftime",
+    format_locale: "auto",
+    output_formats: [
+      { name: "ISO 8601", pattern: "%Y-%m-%dT%H:%M:%S%:z" },
+      { name: "RFC 2822", pattern: "%a, %d %b %Y %H:%M:%S %z" },
+      { name: "Long", pattern: "%A, %B %-d, %Y %-I:%M:%S %p" },
+      { name: "Epoch seconds", pattern: "%s" },
+      { name: "Epoch milliseconds", pattern: "%s%3f" },
+      { name: "Epoch microseconds", pattern: "%s%6f" },
+    ],
     hud_position: "top_center",
+    timezone: "UTC",
+    calendar_system: "none",
+    date_literals: false,
+    date_order: "auto",
+    duration_mode: false,
+    natural_language: false,
+    date_math: false,
+    auto_replace: false,
+    cron_mode: false,
+    cron_runs: 5,
+    history_limit: 100,
   });
   const [autostart, setAutostart] = useState(false);
   const [saving, setSaving] = useState(false);
   const [saveSuccess, setSaveSuccess] = useState(false);
+  const [saveError, setSaveError] = useState<string | null>(null);
+  const [ruleStats, setRuleStats] = useState<RuleStats | null>(null);
+  const [formatPreview, setFormatPreview] = useState<string | null>(null);
+  const [formatError, setFormatError] = useState<string | null>(null);
+  const [expression, setExpression] = useState("");
+  const [evaluation, setEvaluation] = useState<string | null>(null);
+  const [historyFilter, setHistoryFilter] = useState<HistoryFilter>({
+    text: "",
+    from: "",
+    to: "",
+    detector: "",
+    pinned_only: false,
+  });
+  const [historyEntries, setHistoryEntries] = useState<HistoryEntry[]>([]);
+  const [exportMessage, setExportMessage] = useState<string | null>(null);
+  const [eventDraft, setEventDraft] = useState<EventDraft | null>(null);
+  const [eventMessage, setEventMessage] = useState<string | null>(null);
 
   // Load settings function
   const loadSettingsFromStore = useCallback(async () => {
@@ -52,6 +269,12 @@ export default function SettingsView() {
       console.error("Failed to load settings:", error);
     }
 
+    try {
+      setRuleStats(await invoke<RuleStats>("get_rule_stats"));
+    } catch (error) {
+      console.error("Failed to load rule stats:", error);
+    }
+
     try {
       const enabled = await isEnabled();
       setAutostart(enabled);
@@ -91,19 +314,24 @@ export default function SettingsView() {
   const saveSettings = async () => {
     setSaving(true);
     setSaveSuccess(false);
+    setSaveError(null);
     try {
-      await invoke("save_settings", {
-        minYear: settings.min_year,
-        maxYear: settings.max_year,
-        displayDurationMs: settings.display_duration_ms,
-        timeFormat: settings.time_format,
-        hudPosition: settings.hud_position,
-      });
+      await invoke("save_settings", { config: settings });
       setSaveSuccess(true);
       // Hide success message after 2 seconds
       setTimeout(() => setSaveSuccess(false), 2000);
     } catch (error) {
       console.error("Failed to save settings:", error);
+      const settingsError = error as SettingsError;
+      setSaveError(
+        settingsError.kind === "invalid_format"
+          ? t("settings.invalidFormat", { field: settingsError.field, token: settingsError.token })
+          : settingsError.kind === "invalid_rule"
+            ? t("settings.invalidRule", { field: settingsError.field, message: settingsError.message })
+            : settingsError.kind === "invalid_detector"
+              ? t("settings.invalidDetector", { field: settingsError.field, message: settingsError.message })
+              : String(settingsError.message ?? error)
+      );
     }
     setSaving(false);
   };
@@ -123,10 +351,252 @@ export default function SettingsView() {
     }
   };
 
-  const handleChange = (field: keyof Settings, value: string | number) => {
+  // Preview the time format live as it is edited
+  useEffect(() => {
+    const timer = setTimeout(async () => {
+      try {
+        setFormatPreview(
+          await invoke<string>("preview_format", {
+            pattern: settings.time_format,
+            dialect: settings.format_dialect,
+          })
+        );
+        setFormatError(null);
+      } catch (error) {
+        const { token } = error as { token: string; position: number };
+        setFormatPreview(null);
+        setFormatError(t("settings.invalidFormatToken", { token }));
+      }
+    }, 200);
+    return () => clearTimeout(timer);
+  }, [settings.time_format, settings.format_dialect, t]);
+
+  // The history filter as a `query_history` query, with dates as local-day bounds
+  const historyQuery = useCallback(() => ({
+    text: historyFilter.text,
+    from: historyFilter.from ? new Date(`${historyFilter.from}T00:00:00`).getTime() : null,
+    to: historyFilter.to ? new Date(`${historyFilter.to}T23:59:59.999`).getTime() : null,
+    detector: historyFilter.detector || null,
+    pinned_only: historyFilter.pinned_only,
+  }), [historyFilter]);
+
+  const searchHistory = useCallback(async () => {
+    try {
+      setHistoryEntries(await invoke<HistoryEntry[]>("query_history", { query: historyQuery() }));
+    } catch (error) {
+      console.error("Failed to query history:", error);
+    }
+  }, [historyQuery]);
+
+  // Search the history as the filter is edited
+  useEffect(() => {
+    const timer = setTimeout(searchHistory, 200);
+    return () => clearTimeout(timer);
+  }, [searchHistory]);
+
+  // Pick up copies decoded while the window was hidden
+  useEffect(() => {
+    const unlistenPromise = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) {
+        searchHistory();
+      }
+    });
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, [searchHistory]);
+
+  // Edit calendar events the HUD hands over; a newer one replaces the draft
+  useEffect(() => {
+    const unlistenPromise = listen<EventDraft>("edit_calendar_event", (event) => {
+      setEventDraft(event.payload);
+      setEventMessage(null);
+    });
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  // Save the drafted event as an .ics file where the user picks
+  const saveEvent = async () => {
+    if (!eventDraft) return;
+    try {
+      const path = await invoke<string | null>("export_ics", { event: eventDraft.event });
+      if (path) {
+        setEventDraft(null);
+        setEventMessage(t("settings.eventSaved", { path }));
+      }
+    } catch (error) {
+      setEventMessage(t("settings.eventSaveFailed", { message: String(error) }));
+    }
+  };
+
+  const updateEventDraft = (changes: Partial<EventDraft["event"]>) => {
+    setEventDraft((prev) => prev && { ...prev, event: { ...prev.event, ...changes } });
+  };
+
+  const togglePinned = async (entry: HistoryEntry) => {
+    try {
+      await invoke("pin_history_entry", { id: entry.id, pinned: !entry.pinned });
+      await searchHistory();
+    } catch (error) {
+      console.error("Failed to pin history entry:", error);
+    }
+  };
+
+  const exportHistory = async (format: string) => {
+    setExportMessage(null);
+    try {
+      const path = await invoke<string | null>("export_history", { query: historyQuery(), format });
+      if (path) {
+        setExportMessage(t("settings.historyExported", { path }));
+      }
+    } catch (error) {
+      setExportMessage(t("settings.historyExportFailed", { message: String(error) }));
+    }
+  };
+
+  const updateHistoryFilter = <K extends keyof HistoryFilter>(field: K, value: HistoryFilter[K]) => {
+    setHistoryFilter((prev) => ({ ...prev, [field]: value }));
+  };
+
+  // Evaluate a date expression with the saved zone and date order
+  const evaluateExpression = async () => {
+    if (!expression.trim()) {
+      setEvaluation(null);
+      return;
+    }
+    try {
+      const result = await invoke<{
+        kind: string;
+        formatted_time: string;
+        timestamp_seconds: number;
+        timestamp_milliseconds: number;
+      }>("evaluate_date_expression", { expression });
+      setEvaluation(
+        result.kind === "duration"
+          ? t("settings.dateMathDuration", {
+              human: result.formatted_time,
+              milliseconds: result.timestamp_milliseconds,
+            })
+          : t("settings.dateMathInstant", {
+              time: result.formatted_time,
+              seconds: result.timestamp_seconds,
+            })
+      );
+    } catch {
+      setEvaluation(t("settings.dateMathInvalid"));
+    }
+  };
+
+  const handleChange = (
+    field: keyof Settings,
+    value:
+      | string
+      | number
+      | boolean
+      | OutputFormat[]
+      | RecentWindow
+      | ContentRule[]
+      | CustomDetector[]
+      | null
+  ) => {
     setSettings((prev) => ({ ...prev, [field]: value }));
   };
 
+  // The recent window is edited in days and stored in seconds
+  const toggleRecentWindow = () => {
+    handleChange(
+      "recent_window",
+      settings.recent_window
+        ? null
+        : { before_seconds: 5 * 365 * SECONDS_PER_DAY, after_seconds: 365 * SECONDS_PER_DAY }
+    );
+  };
+
+  const updateRecentWindow = (field: keyof RecentWindow, days: number) => {
+    if (!settings.recent_window || Number.isNaN(days)) return;
+    handleChange("recent_window", {
+      ...settings.recent_window,
+      [field]: Math.max(0, Math.round(days * SECONDS_PER_DAY)),
+    });
+  };
+
+  // Edit the ignore and allow rule lists
+  const updateRule = (field: RuleField, index: number, key: keyof ContentRule, value: string) => {
+    handleChange(
+      field,
+      settings[field].map((rule, i) => (i === index ? { ...rule, [key]: value } : rule))
+    );
+  };
+
+  const removeRule = (field: RuleField, index: number) => {
+    handleChange(field, settings[field].filter((_, i) => i !== index));
+  };
+
+  const addRule = (field: RuleField) => {
+    handleChange(field, [...settings[field], { name: "", pattern: "" }]);
+  };
+
+  // Edit the custom detector list
+  const updateDetector = (index: number, key: keyof CustomDetector, value: string) => {
+    handleChange(
+      "custom_detectors",
+      settings.custom_detectors.map((detector, i) => (i === index ? { ...detector, [key]: value } : detector))
+    );
+  };
+
+  const removeDetector = (index: number) => {
+    handleChange("custom_detectors", settings.custom_detectors.filter((_, i) => i !== index));
+  };
+
+  const addDetector = () => {
+    handleChange("custom_detectors", [
+      ...settings.custom_detectors,
+      { label: "", pattern: "^T\\+(\\d+)$", origin: "", unit: "seconds" },
+    ]);
+  };
+
+  // Hits are reported per compiled pattern, so match on the saved pattern
+  const ruleHits = (field: RuleField, pattern: string) => {
+    const stats = field === "ignore_rules" ? ruleStats?.ignore : ruleStats?.allow;
+    return stats?.find((rule) => rule.pattern === pattern)?.hits;
+  };
+
+  const isPresetFormat =
+    settings.format_dialect === "strftime" &&
+    TIME_FORMATS.some((format) => format.value === settings.time_format);
+
+  const selectTimeFormatPreset = (value: string) => {
+    if (value === CUSTOM_FORMAT) return;
+    handleChange("time_format", value);
+    handleChange("format_dialect", "strftime");
+  };
+
+  // Edit the ordered list of "copy as" output formats
+  const updateOutputFormat = (index: number, field: keyof OutputFormat, value: string) => {
+    handleChange(
+      "output_formats",
+      settings.output_formats.map((format, i) => (i === index ? { ...format, [field]: value } : format))
+    );
+  };
+
+  const moveOutputFormat = (index: number, delta: number) => {
+    const target = index + delta;
+    if (target < 0 || target >= settings.output_formats.length) return;
+    const formats = [...settings.output_formats];
+    [formats[index], formats[target]] = [formats[target], formats[index]];
+    handleChange("output_formats", formats);
+  };
+
+  const removeOutputFormat = (index: number) => {
+    handleChange("output_formats", settings.output_formats.filter((_, i) => i !== index));
+  };
+
+  const addOutputFormat = () => {
+    handleChange("output_formats", [...settings.output_formats, { name: "", pattern: "%Y-%m-%d" }]);
+  };
+
   return (
     <div style={{ 
       minHeight: '100%',
@@ -143,6 +613,108 @@ export default function SettingsView() {
         {t("settings.title")}
       </h1>
 
+      {/* Calendar Event Editor */}
+      {(eventDraft || eventMessage) && (
+        <div style={{
+          background: 'white',
+          borderRadius: 12,
+          padding: '14px 16px',
+          marginBottom: 16,
+          boxShadow: '0 1px 3px rgba(0,0,0,0.08), 0 1px 2px rgba(0,0,0,0.06)'
+        }}>
+          {eventDraft && (
+            <>
+              <div style={{
+                display: 'flex',
+                alignItems: 'center',
+                justifyContent: 'space-between',
+                marginBottom: 10
+              }}>
+                <span style={{ fontSize: 14, color: '#334155' }}>{t("settings.calendarEvent")}</span>
+                <span style={{ fontSize: 12, color: '#64748b' }}>{eventDraft.formatted_time}</span>
+              </div>
+              <input
+                type="text"
+                value={eventDraft.event.title}
+                autoFocus
+                placeholder={t("settings.eventTitleLabel")}
+                onChange={(e) => updateEventDraft({ title: e.target.value })}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") saveEvent();
+                  if (e.key === "Escape") setEventDraft(null);
+                }}
+                style={{
+                  width: '100%',
+                  boxSizing: 'border-box',
+                  padding: '6px 8px',
+                  borderRadius: 6,
+                  border: '1px solid #e2e8f0',
+                  background: '#f8fafc',
+                  fontSize: 13,
+                  outline: 'none',
+                  color: '#334155'
+                }}
+              />
+              <div style={{ display: 'flex', alignItems: 'center', gap: 6, marginTop: 8 }}>
+                <span style={{ fontSize: 13, color: '#64748b' }}>{t("settings.alarm")}</span>
+                <select
+                  value={eventDraft.event.alarm_minutes === null ? "" : String(eventDraft.event.alarm_minutes)}
+                  onChange={(e) => updateEventDraft({
+                    alarm_minutes: e.target.value === "" ? null : Number(e.target.value),
+                  })}
+                  style={{
+                    flex: 1,
+                    fontSize: 13,
+                    color: '#64748b',
+                    background: 'transparent',
+                    border: 'none',
+                    outline: 'none',
+                    cursor: 'pointer'
+                  }}
+                >
+                  {ALARM_OPTIONS.map((option) => (
+                    <option key={option.value} value={option.value}>{t(option.labelKey)}</option>
+                  ))}
+                </select>
+                <button
+                  onClick={() => setEventDraft(null)}
+                  style={{
+                    padding: '4px 10px',
+                    borderRadius: 6,
+                    border: 'none',
+                    background: '#f1f5f9',
+                    color: '#64748b',
+                    fontSize: 13,
+                    cursor: 'pointer'
+                  }}
+                >
+                  {t("settings.cancelEvent")}
+                </button>
+                <button
+                  onClick={saveEvent}
+                  style={{
+                    padding: '4px 10px',
+                    borderRadius: 6,
+                    border: 'none',
+                    background: '#3b82f6',
+                    color: 'white',
+                    fontSize: 13,
+                    cursor: 'pointer'
+                  }}
+                >
+                  {t("settings.saveEvent")}
+                </button>
+              </div>
+            </>
+          )}
+          {eventMessage && (
+            <p style={{ fontSize: 12, color: '#64748b', marginTop: eventDraft ? 8 : 0, marginBottom: 0, wordBreak: 'break-all' }}>
+              {eventMessage}
+            </p>
+          )}
+        </div>
+      )}
+
       {/* Main Settings Card */}
       <div style={{ 
         background: 'white',
@@ -160,33 +732,7 @@ export default function SettingsView() {
           borderBottom: '1px solid #f1f5f9'
         }}>
           <span style={{ fontSize: 14, color: '#334155' }}>{t("settings.launchAtLogin")}</span>
-          <button
-            onClick={toggleAutostart}
-            style={{
-              width: 44,
-              height: 26,
-              borderRadius: 13,
-              border: 'none',
-              background: autostart ? '#22c55e' : '#e2e8f0',
-              position: 'relative',
-              cursor: 'pointer',
-              transition: 'background 0.2s'
-            }}
-          >
-            <span
-              style={{
-                position: 'absolute',
-                top: 2,
-                left: autostart ? 20 : 2,
-                width: 22,
-                height: 22,
-                borderRadius: 11,
-                background: 'white',
-                boxShadow: '0 1px 3px rgba(0,0,0,0.2)',
-                transition: 'left 0.2s'
-              }}
-            />
-          </button>
+          <ToggleSwitch checked={autostart} onToggle={toggleAutostart} />
         </div>
 
         {/* Display Duration */}
@@ -234,8 +780,8 @@ export default function SettingsView() {
         }}>
           <span style={{ fontSize: 14, color: '#334155' }}>{t("settings.timeFormat")}</span>
           <select
-            value={settings.time_format}
-            onChange={(e) => handleChange("time_format", e.target.value)}
+            value={isPresetFormat ? settings.time_format : CUSTOM_FORMAT}
+            onChange={(e) => selectTimeFormatPreset(e.target.value)}
             style={{
               fontSize: 14,
               color: '#64748b',
@@ -251,9 +797,97 @@ export default function SettingsView() {
                 {format.label}
               </option>
             ))}
+            <option value={CUSTOM_FORMAT}>{t("settings.timeFormatCustom")}</option>
           </select>
         </div>
 
+        {/* Custom time format with live preview */}
+        <div style={{
+          padding: '10px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <div style={{
+            display: 'flex',
+            alignItems: 'center',
+            justifyContent: 'space-between',
+            marginBottom: 8
+          }}>
+            <span style={{ fontSize: 13, color: '#64748b' }}>{t("settings.formatDialect")}</span>
+            <select
+              value={settings.format_dialect}
+              onChange={(e) => handleChange("format_dialect", e.target.value)}
+              style={{
+                fontSize: 13,
+                color: '#64748b',
+                background: 'transparent',
+                border: 'none',
+                outline: 'none',
+                textAlign: 'right',
+                cursor: 'pointer'
+              }}
+            >
+              {FORMAT_DIALECTS.map((dialect) => (
+                <option key={dialect.value} value={dialect.value}>
+                  {dialect.label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div style={{
+            display: 'flex',
+            alignItems: 'center',
+            justifyContent: 'space-between',
+            marginBottom: 8
+          }}>
+            <span style={{ fontSize: 13, color: '#64748b' }}>{t("settings.formatLocale")}</span>
+            <select
+              value={settings.format_locale}
+              onChange={(e) => handleChange("format_locale", e.target.value)}
+              style={{
+                fontSize: 13,
+                color: '#64748b',
+                background: 'transparent',
+                border: 'none',
+                outline: 'none',
+                textAlign: 'right',
+                cursor: 'pointer'
+              }}
+            >
+              <option value="auto">{t("settings.formatLocaleAuto")}</option>
+              {FORMAT_LOCALES.map((locale) => (
+                <option key={locale.value} value={locale.value}>
+                  {locale.label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <input
+            type="text"
+            value={settings.time_format}
+            onChange={(e) => handleChange("time_format", e.target.value)}
+            spellCheck={false}
+            style={{
+              width: '100%',
+              boxSizing: 'border-box',
+              padding: '6px 8px',
+              borderRadius: 6,
+              border: `1px solid ${formatError ? '#fca5a5' : '#e2e8f0'}`,
+              background: '#f8fafc',
+              fontSize: 13,
+              fontFamily: 'monospace',
+              outline: 'none',
+              color: '#334155'
+            }}
+          />
+          <p style={{
+            margin: '6px 0 0',
+            fontSize: 12,
+            color: formatError ? '#ef4444' : '#94a3b8'
+          }}>
+            {formatError ?? (formatPreview && t("settings.timeFormatPreview", { preview: formatPreview }))}
+          </p>
+        </div>
+
         {/* HUD Position */}
         <div style={{
           display: 'flex',
@@ -284,12 +918,258 @@ export default function SettingsView() {
           </select>
         </div>
 
+        {/* Time Zone */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }}>{t("settings.timezone")}</span>
+          <input
+            type="text"
+            value={settings.timezone}
+            placeholder="UTC"
+            title={t("settings.timezoneDesc")}
+            onChange={(e) => handleChange("timezone", e.target.value)}
+            style={{
+              width: 150,
+              padding: '6px 8px',
+              borderRadius: 6,
+              border: '1px solid #e2e8f0',
+              background: '#f8fafc',
+              fontSize: 13,
+              textAlign: 'right',
+              outline: 'none',
+              color: '#334155'
+            }}
+          />
+        </div>
+
+        {/* Date Literals */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }} title={t("settings.dateLiteralsDesc")}>
+            {t("settings.dateLiterals")}
+          </span>
+          <ToggleSwitch
+            checked={settings.date_literals}
+            onToggle={() => handleChange("date_literals", !settings.date_literals)}
+          />
+        </div>
+
+        {/* Date Order */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }}>{t("settings.dateOrder")}</span>
+          <select
+            value={settings.date_order}
+            onChange={(e) => handleChange("date_order", e.target.value)}
+            style={{
+              fontSize: 14,
+              color: '#64748b',
+              background: 'transparent',
+              border: 'none',
+              outline: 'none',
+              textAlign: 'right',
+              cursor: 'pointer'
+            }}
+          >
+            {DATE_ORDERS.map((order) => (
+              <option key={order.value} value={order.value}>
+                {t(order.labelKey)}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        {/* Secondary Calendar */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }}>{t("settings.calendarSystem")}</span>
+          <select
+            value={settings.calendar_system}
+            onChange={(e) => handleChange("calendar_system", e.target.value)}
+            style={{
+              fontSize: 14,
+              color: '#64748b',
+              background: 'transparent',
+              border: 'none',
+              outline: 'none',
+              textAlign: 'right',
+              cursor: 'pointer'
+            }}
+          >
+            {CALENDAR_SYSTEMS.map((system) => (
+              <option key={system.value} value={system.value}>
+                {t(system.labelKey)}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        {/* Auto Replace */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }} title={t("settings.autoReplaceDesc")}>
+            {t("settings.autoReplace")}
+          </span>
+          <ToggleSwitch
+            checked={settings.auto_replace}
+            onToggle={() => handleChange("auto_replace", !settings.auto_replace)}
+          />
+        </div>
+
+        {/* Duration Mode */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }}>{t("settings.durationMode")}</span>
+          <ToggleSwitch
+            checked={settings.duration_mode}
+            onToggle={() => handleChange("duration_mode", !settings.duration_mode)}
+          />
+        </div>
+
+        {/* Natural Language */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }} title={t("settings.naturalLanguageDesc")}>
+            {t("settings.naturalLanguage")}
+          </span>
+          <ToggleSwitch
+            checked={settings.natural_language}
+            onToggle={() => handleChange("natural_language", !settings.natural_language)}
+          />
+        </div>
+
+        {/* Date Math */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }} title={t("settings.dateMathDesc")}>
+            {t("settings.dateMath")}
+          </span>
+          <ToggleSwitch
+            checked={settings.date_math}
+            onToggle={() => handleChange("date_math", !settings.date_math)}
+          />
+        </div>
+
+        {/* Cron Explainer */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }} title={t("settings.cronModeDesc")}>
+            {t("settings.cronMode")}
+          </span>
+          <div style={{ display: 'flex', alignItems: 'center', gap: 12 }}>
+            {settings.cron_mode && (
+              <input
+                type="number"
+                min="1"
+                max="20"
+                value={settings.cron_runs}
+                onChange={(e) => handleChange("cron_runs", parseInt(e.target.value))}
+                title={t("settings.cronRuns")}
+                style={{
+                  width: 50,
+                  padding: '6px 8px',
+                  borderRadius: 6,
+                  border: '1px solid #e2e8f0',
+                  background: '#f8fafc',
+                  fontSize: 13,
+                  fontWeight: 500,
+                  textAlign: 'center',
+                  outline: 'none',
+                  color: '#334155'
+                }}
+              />
+            )}
+            <ToggleSwitch
+              checked={settings.cron_mode}
+              onToggle={() => handleChange("cron_mode", !settings.cron_mode)}
+            />
+          </div>
+        </div>
+
+        {/* History Size */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }} title={t("settings.historyLimitDesc")}>
+            {t("settings.historyLimit")}
+          </span>
+          <input
+            type="number"
+            min="0"
+            max="1000"
+            value={settings.history_limit}
+            onChange={(e) => handleChange("history_limit", parseInt(e.target.value))}
+            style={{
+              width: 70,
+              padding: '6px 8px',
+              borderRadius: 6,
+              border: '1px solid #e2e8f0',
+              background: '#f8fafc',
+              fontSize: 13,
+              fontWeight: 500,
+              textAlign: 'center',
+              outline: 'none',
+              color: '#334155'
+            }}
+          />
+        </div>
+
         {/* Year Range - inline */}
         <div style={{
           display: 'flex',
           alignItems: 'center',
           justifyContent: 'space-between',
-          padding: '14px 16px'
+          padding: '14px 16px',
+          borderBottom: '1px solid #f1f5f9'
         }}>
           <span style={{ fontSize: 14, color: '#334155' }}>{t("settings.yearRange")}</span>
           <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
@@ -334,6 +1214,605 @@ export default function SettingsView() {
             />
           </div>
         </div>
+
+        {/* Recent Window - combined with the year range */}
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          padding: '14px 16px'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }} title={t("settings.recentWindowDesc")}>
+            {t("settings.recentWindow")}
+          </span>
+          <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+            {settings.recent_window && (
+              <>
+                <input
+                  type="number"
+                  min="0"
+                  value={Math.round(settings.recent_window.before_seconds / SECONDS_PER_DAY)}
+                  onChange={(e) => updateRecentWindow("before_seconds", parseFloat(e.target.value))}
+                  title={t("settings.recentWindowBefore")}
+                  style={{
+                    width: 60,
+                    padding: '6px 8px',
+                    borderRadius: 6,
+                    border: '1px solid #e2e8f0',
+                    background: '#f8fafc',
+                    fontSize: 13,
+                    fontWeight: 500,
+                    textAlign: 'center',
+                    outline: 'none',
+                    color: '#334155'
+                  }}
+                />
+                <span style={{ color: '#94a3b8', fontSize: 12 }}>{t("settings.recentWindowNow")}</span>
+                <input
+                  type="number"
+                  min="0"
+                  value={Math.round(settings.recent_window.after_seconds / SECONDS_PER_DAY)}
+                  onChange={(e) => updateRecentWindow("after_seconds", parseFloat(e.target.value))}
+                  title={t("settings.recentWindowAfter")}
+                  style={{
+                    width: 60,
+                    padding: '6px 8px',
+                    borderRadius: 6,
+                    border: '1px solid #e2e8f0',
+                    background: '#f8fafc',
+                    fontSize: 13,
+                    fontWeight: 500,
+                    textAlign: 'center',
+                    outline: 'none',
+                    color: '#334155'
+                  }}
+                />
+                <span style={{ color: '#94a3b8', fontSize: 12 }}>{t("settings.recentWindowDays")}</span>
+              </>
+            )}
+            <ToggleSwitch checked={!!settings.recent_window} onToggle={toggleRecentWindow} />
+          </div>
+        </div>
+      </div>
+
+      {/* Output Formats */}
+      <div style={{
+        background: 'white',
+        borderRadius: 12,
+        padding: '14px 16px',
+        marginBottom: 16,
+        boxShadow: '0 1px 3px rgba(0,0,0,0.08), 0 1px 2px rgba(0,0,0,0.06)'
+      }}>
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between',
+          marginBottom: 10
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }} title={t("settings.outputFormatsDesc")}>
+            {t("settings.outputFormats")}
+          </span>
+          <button
+            onClick={addOutputFormat}
+            style={{
+              padding: '4px 10px',
+              borderRadius: 6,
+              border: 'none',
+              background: '#eff6ff',
+              color: '#3b82f6',
+              fontSize: 13,
+              cursor: 'pointer'
+            }}
+          >
+            {t("settings.outputFormatsAdd")}
+          </button>
+        </div>
+        {settings.output_formats.map((format, index) => (
+          <div key={index} style={{ display: 'flex', alignItems: 'center', gap: 6, marginTop: 6 }}>
+            <input
+              type="text"
+              value={format.name}
+              placeholder={t("settings.outputFormatName")}
+              onChange={(e) => updateOutputFormat(index, "name", e.target.value)}
+              style={{ ...{
+                  padding: '6px 8px',
+                  borderRadius: 6,
+                  border: '1px solid #e2e8f0',
+                  background: '#f8fafc',
+                  fontSize: 13,
+                  outline: 'none',
+                  color: '#334155'
+                }, width: 110 }}
+            />
+            <input
+              type="text"
+              value={format.pattern}
+              placeholder="%Y-%m-%d"
+              onChange={(e) => updateOutputFormat(index, "pattern", e.target.value)}
+              style={{ ...{
+                  padding: '6px 8px',
+                  borderRadius: 6,
+                  border: '1px solid #e2e8f0',
+                  background: '#f8fafc',
+                  fontSize: 13,
+                  outline: 'none',
+                  color: '#334155'
+                }, flex: 1, minWidth: 0, fontFamily: 'monospace' }}
+            />
+            <button onClick={() => moveOutputFormat(index, -1)} style={{
+                  width: 24,
+                  height: 24,
+                  borderRadius: 6,
+                  border: 'none',
+                  background: '#f1f5f9',
+                  color: '#64748b',
+                  cursor: 'pointer',
+                  fontSize: 12
+                }}>↑</button>
+            <button onClick={() => moveOutputFormat(index, 1)} style={{
+                  width: 24,
+                  height: 24,
+                  borderRadius: 6,
+                  border: 'none',
+                  background: '#f1f5f9',
+                  color: '#64748b',
+                  cursor: 'pointer',
+                  fontSize: 12
+                }}>↓</button>
+            <button onClick={() => removeOutputFormat(index)} style={{
+                  width: 24,
+                  height: 24,
+                  borderRadius: 6,
+                  border: 'none',
+                  background: '#f1f5f9',
+                  color: '#64748b',
+                  cursor: 'pointer',
+                  fontSize: 12
+                }}>✕</button>
+          </div>
+        ))}
+      </div>
+
+      {/* Content Rules */}
+      <div style={{
+        background: 'white',
+        borderRadius: 12,
+        padding: '14px 16px',
+        marginBottom: 16,
+        boxShadow: '0 1px 3px rgba(0,0,0,0.08), 0 1px 2px rgba(0,0,0,0.06)'
+      }}>
+        {(["ignore_rules", "allow_rules"] as RuleField[]).map((field) => (
+          <div key={field} style={{ marginBottom: field === "ignore_rules" ? 14 : 0 }}>
+            <div style={{
+              display: 'flex',
+              alignItems: 'center',
+              justifyContent: 'space-between'
+            }}>
+              <span
+                style={{ fontSize: 14, color: '#334155' }}
+                title={t(field === "ignore_rules" ? "settings.ignoreRulesDesc" : "settings.allowRulesDesc")}
+              >
+                {t(field === "ignore_rules" ? "settings.ignoreRules" : "settings.allowRules")}
+              </span>
+              <button
+                onClick={() => addRule(field)}
+                style={{
+                  padding: '4px 10px',
+                  borderRadius: 6,
+                  border: 'none',
+                  background: '#eff6ff',
+                  color: '#3b82f6',
+                  fontSize: 13,
+                  cursor: 'pointer'
+                }}
+              >
+                {t("settings.outputFormatsAdd")}
+              </button>
+            </div>
+            {settings[field].map((rule, index) => {
+              const hits = ruleHits(field, rule.pattern);
+              return (
+                <div key={index} style={{ display: 'flex', alignItems: 'center', gap: 6, marginTop: 6 }}>
+                  <input
+                    type="text"
+                    value={rule.name}
+                    placeholder={t("settings.outputFormatName")}
+                    onChange={(e) => updateRule(field, index, "name", e.target.value)}
+                    style={{ ...{
+                    padding: '6px 8px',
+                    borderRadius: 6,
+                    border: '1px solid #e2e8f0',
+                    background: '#f8fafc',
+                    fontSize: 13,
+                    outline: 'none',
+                    color: '#334155'
+                  }, width: 100 }}
+                  />
+                  <input
+                    type="text"
+                    value={rule.pattern}
+                    placeholder="^1[3-9]\d{9}$"
+                    spellCheck={false}
+                    onChange={(e) => updateRule(field, index, "pattern", e.target.value)}
+                    style={{ ...{
+                    padding: '6px 8px',
+                    borderRadius: 6,
+                    border: '1px solid #e2e8f0',
+                    background: '#f8fafc',
+                    fontSize: 13,
+                    outline: 'none',
+                    color: '#334155'
+                  }, flex: 1, minWidth: 0, fontFamily: 'monospace' }}
+                  />
+                  <span
+                    style={{ fontSize: 12, color: '#94a3b8', minWidth: 28, textAlign: 'right' }}
+                    title={t("settings.ruleHits")}
+                  >
+                    {hits ?? "–"}
+                  </span>
+                  <button
+                    onClick={() => removeRule(field, index)}
+                    style={{
+                      width: 24,
+                      height: 24,
+                      borderRadius: 6,
+                      border: 'none',
+                      background: '#f1f5f9',
+                      color: '#64748b',
+                      cursor: 'pointer',
+                      fontSize: 12
+                    }}
+                  >
+                    ✕
+                  </button>
+                </div>
+              );
+            })}
+          </div>
+        ))}
+        {settings.allow_rules.length > 0 && ruleStats && (
+          <p style={{ margin: '10px 0 0', fontSize: 12, color: '#94a3b8' }}>
+            {t("settings.notAllowedHits", { count: ruleStats.not_allowed })}
+          </p>
+        )}
+      </div>
+
+      {/* Custom Detectors */}
+      <div style={{
+        background: 'white',
+        borderRadius: 12,
+        padding: '14px 16px',
+        marginBottom: 16,
+        boxShadow: '0 1px 3px rgba(0,0,0,0.08), 0 1px 2px rgba(0,0,0,0.06)'
+      }}>
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }} title={t("settings.customDetectorsDesc")}>
+            {t("settings.customDetectors")}
+          </span>
+          <button
+            onClick={addDetector}
+            style={{
+              padding: '4px 10px',
+              borderRadius: 6,
+              border: 'none',
+              background: '#eff6ff',
+              color: '#3b82f6',
+              fontSize: 13,
+              cursor: 'pointer'
+            }}
+          >
+            {t("settings.outputFormatsAdd")}
+          </button>
+        </div>
+        {settings.custom_detectors.map((detector, index) => (
+          <div
+            key={index}
+            style={{
+              display: 'grid',
+              gridTemplateColumns: '1fr 1fr 24px',
+              gap: 6,
+              marginTop: 10,
+              paddingTop: index > 0 ? 10 : 0,
+              borderTop: index > 0 ? '1px solid #f1f5f9' : 'none'
+            }}
+          >
+            <input
+              type="text"
+              value={detector.label}
+              placeholder={t("settings.detectorLabel")}
+              onChange={(e) => updateDetector(index, "label", e.target.value)}
+              style={{
+                padding: '6px 8px',
+                borderRadius: 6,
+                border: '1px solid #e2e8f0',
+                background: '#f8fafc',
+                fontSize: 13,
+                outline: 'none',
+                color: '#334155'
+              }}
+            />
+            <input
+              type="text"
+              value={detector.pattern}
+              placeholder={t("settings.detectorPattern")}
+              spellCheck={false}
+              onChange={(e) => updateDetector(index, "pattern", e.target.value)}
+              style={{ ...{
+                padding: '6px 8px',
+                borderRadius: 6,
+                border: '1px solid #e2e8f0',
+                background: '#f8fafc',
+                fontSize: 13,
+                outline: 'none',
+                color: '#334155'
+              }, fontFamily: 'monospace' }}
+            />
+            <button
+              onClick={() => removeDetector(index)}
+              style={{
+                width: 24,
+                height: 24,
+                borderRadius: 6,
+                border: 'none',
+                background: '#f1f5f9',
+                color: '#64748b',
+                cursor: 'pointer',
+                fontSize: 12
+              }}
+            >
+              ✕
+            </button>
+            <input
+              type="text"
+              value={detector.origin}
+              placeholder={t("settings.detectorOrigin")}
+              onChange={(e) => updateDetector(index, "origin", e.target.value)}
+              style={{
+                padding: '6px 8px',
+                borderRadius: 6,
+                border: '1px solid #e2e8f0',
+                background: '#f8fafc',
+                fontSize: 13,
+                outline: 'none',
+                color: '#334155'
+              }}
+            />
+            <select
+              value={detector.unit}
+              onChange={(e) => updateDetector(index, "unit", e.target.value)}
+              style={{
+                padding: '6px 8px',
+                borderRadius: 6,
+                border: '1px solid #e2e8f0',
+                background: '#f8fafc',
+                fontSize: 13,
+                outline: 'none',
+                color: '#334155'
+              }}
+            >
+              {TIME_UNITS.map((unit) => (
+                <option key={unit} value={unit}>
+                  {t(`settings.unit_${unit}`)}
+                </option>
+              ))}
+            </select>
+          </div>
+        ))}
+      </div>
+
+      {/* Date Calculator */}
+      <div style={{
+        background: 'white',
+        borderRadius: 12,
+        padding: '14px 16px',
+        marginBottom: 16,
+        boxShadow: '0 1px 3px rgba(0,0,0,0.08), 0 1px 2px rgba(0,0,0,0.06)'
+      }}>
+        <span style={{ fontSize: 14, color: '#334155' }}>{t("settings.dateCalculator")}</span>
+        <input
+          type="text"
+          value={expression}
+          placeholder="now - 90m"
+          onChange={(e) => setExpression(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && evaluateExpression()}
+          onBlur={evaluateExpression}
+          style={{
+            display: 'block',
+            width: '100%',
+            boxSizing: 'border-box',
+            marginTop: 10,
+            padding: '6px 8px',
+            borderRadius: 6,
+            border: '1px solid #e2e8f0',
+            background: '#f8fafc',
+            fontSize: 13,
+            fontFamily: 'monospace',
+            outline: 'none',
+            color: '#334155'
+          }}
+        />
+        {evaluation && (
+          <p style={{ fontSize: 13, color: '#64748b', marginTop: 8, fontFamily: 'monospace' }}>
+            {evaluation}
+          </p>
+        )}
+      </div>
+
+      {/* History */}
+      <div style={{
+        background: 'white',
+        borderRadius: 12,
+        padding: '14px 16px',
+        marginBottom: 16,
+        boxShadow: '0 1px 3px rgba(0,0,0,0.08), 0 1px 2px rgba(0,0,0,0.06)'
+      }}>
+        <div style={{
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'space-between'
+        }}>
+          <span style={{ fontSize: 14, color: '#334155' }} title={t("settings.historyDesc")}>
+            {t("settings.history")}
+          </span>
+          <div style={{ display: 'flex', gap: 6 }}>
+            {EXPORT_FORMATS.map((format) => (
+              <button
+                key={format.value}
+                onClick={() => exportHistory(format.value)}
+                title={t("settings.historyExport")}
+                style={{
+                  padding: '4px 10px',
+                  borderRadius: 6,
+                  border: '1px solid #e2e8f0',
+                  background: '#f8fafc',
+                  fontSize: 12,
+                  color: '#334155',
+                  cursor: 'pointer'
+                }}
+              >
+                {format.label}
+              </button>
+            ))}
+          </div>
+        </div>
+
+        <input
+          type="text"
+          value={historyFilter.text}
+          placeholder={t("settings.historySearch")}
+          onChange={(e) => updateHistoryFilter("text", e.target.value)}
+          style={{
+            display: 'block',
+            width: '100%',
+            boxSizing: 'border-box',
+            marginTop: 10,
+            padding: '6px 8px',
+            borderRadius: 6,
+            border: '1px solid #e2e8f0',
+            background: '#f8fafc',
+            fontSize: 13,
+            outline: 'none',
+            color: '#334155'
+          }}
+        />
+        <div style={{ display: 'flex', alignItems: 'center', gap: 6, marginTop: 8 }}>
+          {(["from", "to"] as const).map((field) => (
+            <input
+              key={field}
+              type="date"
+              value={historyFilter[field]}
+              onChange={(e) => updateHistoryFilter(field, e.target.value)}
+              title={t(field === "from" ? "settings.historyFrom" : "settings.historyTo")}
+              style={{
+                flex: 1,
+                minWidth: 0,
+                padding: '5px 6px',
+                borderRadius: 6,
+                border: '1px solid #e2e8f0',
+                background: '#f8fafc',
+                fontSize: 12,
+                outline: 'none',
+                color: '#334155'
+              }}
+            />
+          ))}
+          <select
+            value={historyFilter.detector}
+            onChange={(e) => updateHistoryFilter("detector", e.target.value)}
+            style={{
+              flex: 1,
+              minWidth: 0,
+              padding: '5px 6px',
+              borderRadius: 6,
+              border: '1px solid #e2e8f0',
+              background: '#f8fafc',
+              fontSize: 12,
+              outline: 'none',
+              color: '#334155'
+            }}
+          >
+            <option value="">{t("settings.historyAllTypes")}</option>
+            {HISTORY_TYPES.map((type) => (
+              <option key={type} value={type}>{t(`settings.historyType_${type}`)}</option>
+            ))}
+            {settings.custom_detectors.map((detector) => (
+              <option key={detector.label} value={`custom:${detector.label}`}>{detector.label}</option>
+            ))}
+          </select>
+          <button
+            onClick={() => updateHistoryFilter("pinned_only", !historyFilter.pinned_only)}
+            title={t("settings.historyPinnedOnly")}
+            style={{
+              padding: '4px 8px',
+              borderRadius: 6,
+              border: '1px solid #e2e8f0',
+              background: historyFilter.pinned_only ? '#dbeafe' : '#f8fafc',
+              fontSize: 12,
+              cursor: 'pointer'
+            }}
+          >
+            📌
+          </button>
+        </div>
+
+        <div style={{ marginTop: 8, maxHeight: 220, overflowY: 'auto' }}>
+          {historyEntries.length === 0 ? (
+            <p style={{ fontSize: 12, color: '#94a3b8', margin: '8px 0 0' }}>{t("settings.historyEmpty")}</p>
+          ) : historyEntries.map((entry) => (
+            <div
+              key={entry.id}
+              style={{
+                display: 'flex',
+                alignItems: 'center',
+                gap: 8,
+                padding: '6px 0',
+                borderBottom: '1px solid #f1f5f9'
+              }}
+            >
+              <button
+                onClick={() => togglePinned(entry)}
+                title={t(entry.pinned ? "settings.historyUnpin" : "settings.historyPin")}
+                style={{
+                  border: 'none',
+                  background: 'transparent',
+                  cursor: 'pointer',
+                  fontSize: 12,
+                  opacity: entry.pinned ? 1 : 0.3
+                }}
+              >
+                📌
+              </button>
+              <div style={{ flex: 1, minWidth: 0 }}>
+                <div style={{
+                  fontSize: 12,
+                  fontFamily: 'monospace',
+                  color: '#334155',
+                  overflow: 'hidden',
+                  textOverflow: 'ellipsis',
+                  whiteSpace: 'nowrap'
+                }}>
+                  {entry.raw_value}
+                </div>
+                <div style={{ fontSize: 12, color: '#64748b' }}>
+                  {entry.formatted_time} · {entry.interpretation.detector ?? t(`settings.historyType_${entry.interpretation.type}`)}
+                </div>
+              </div>
+              <span style={{ fontSize: 11, color: '#94a3b8', whiteSpace: 'nowrap' }}>
+                {new Date(entry.detected_at).toLocaleString()}
+              </span>
+            </div>
+          ))}
+        </div>
+
+        {exportMessage && (
+          <p style={{ fontSize: 12, color: '#64748b', marginTop: 8, wordBreak: 'break-all' }}>
+            {exportMessage}
+          </p>
+        )}
       </div>
 
       {/* Save Button */}
@@ -359,6 +1838,17 @@ export default function SettingsView() {
         {saving ? t("settings.saving") : saveSuccess ? t("settings.saved") : t("settings.save")}
       </button>
 
+      {saveError && (
+        <p style={{
+          textAlign: 'center',
+          fontSize: 12,
+          color: '#ef4444',
+          marginTop: 8
+        }}>
+          {saveError}
+        </p>
+      )}
+
       {/* Footer */}
       <p style={{
         textAlign: 'center',